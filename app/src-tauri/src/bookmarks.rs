@@ -0,0 +1,377 @@
+// ============== BROWSER BOOKMARKS IMPORT ==============
+//
+// Reads Netscape bookmark HTML (what every browser exports), Chrome's
+// `Bookmarks` JSON and Firefox's `places.sqlite`. Each folder becomes a hub
+// node (`void://bookmarks/<folder path>`) linked to its bookmarks and to its
+// sub-folders, so folders show up as clusters in the void.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use rand::Rng;
+use rusqlite::{Connection, params};
+use scraper::{ElementRef, Html, Selector};
+
+/// Scheme used for folder hub nodes. These are skipped by crawling and discovery.
+pub const BOOKMARK_HUB_PREFIX: &str = "void://bookmarks/";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkImportStats {
+    pub format: String,
+    pub bookmarks_found: i32,
+    pub folders: i32,
+    pub nodes_imported: i32,
+    /// Bookmarks whose URL was already in the void; they get linked, not duplicated
+    pub nodes_existing: i32,
+    /// Non-web bookmarks (javascript:, place:, chrome://...) and repeats within the file
+    pub bookmarks_skipped: i32,
+    pub edges_added: i32,
+}
+
+#[derive(Debug, Clone)]
+struct Bookmark {
+    folder: Vec<String>,
+    title: String,
+    url: String,
+    icon: Option<String>,
+    added_at: Option<String>,
+}
+
+/// A node id and its position.
+type PlacedNode = (i64, (f64, f64, f64));
+
+fn format_timestamp(secs: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(secs, 0).map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+// ----- Netscape bookmark HTML -----
+
+/// Folder names of every enclosing `<DL>`, outermost first. In the Netscape
+/// format a folder's `<H3>` title is the nearest preceding sibling of its `<DL>`.
+fn netscape_folder_path(link: &ElementRef) -> Vec<String> {
+    let mut path = vec![];
+    for ancestor in link.ancestors() {
+        let Some(el) = ElementRef::wrap(ancestor) else { continue };
+        if el.value().name() != "dl" {
+            continue;
+        }
+        let heading = ancestor.prev_siblings()
+            .filter_map(ElementRef::wrap)
+            .find(|s| s.value().name() == "h3");
+        if let Some(h3) = heading {
+            path.push(h3.text().collect::<String>().trim().to_string());
+        }
+    }
+    path.reverse();
+    path
+}
+
+fn parse_netscape_html(html: &str) -> Vec<Bookmark> {
+    let document = Html::parse_document(html);
+    let Ok(link_selector) = Selector::parse("a[href]") else { return vec![] };
+
+    document.select(&link_selector)
+        .filter_map(|link| {
+            let url = link.value().attr("href")?.trim().to_string();
+            let title = link.text().collect::<String>().trim().to_string();
+            Some(Bookmark {
+                folder: netscape_folder_path(&link),
+                title,
+                url,
+                icon: link.value().attr("icon").map(|i| i.to_string()).filter(|i| i.starts_with("data:")),
+                added_at: link.value().attr("add_date")
+                    .and_then(|d| d.parse::<i64>().ok())
+                    .and_then(format_timestamp),
+            })
+        })
+        .collect()
+}
+
+// ----- Chrome `Bookmarks` JSON -----
+
+/// Chrome stores times as microseconds since 1601-01-01.
+fn chrome_time(value: &Value) -> Option<String> {
+    let micros: i64 = value.as_str()?.parse().ok()?;
+    format_timestamp(micros / 1_000_000 - 11_644_473_600)
+}
+
+fn walk_chrome_node(node: &Value, folder: &mut Vec<String>, out: &mut Vec<Bookmark>) {
+    match node.get("type").and_then(|t| t.as_str()) {
+        Some("url") => {
+            if let Some(url) = node.get("url").and_then(|u| u.as_str()) {
+                out.push(Bookmark {
+                    folder: folder.clone(),
+                    title: node.get("name").and_then(|n| n.as_str()).unwrap_or_default().to_string(),
+                    url: url.to_string(),
+                    icon: None,
+                    added_at: node.get("date_added").and_then(chrome_time),
+                });
+            }
+        }
+        Some("folder") => {
+            folder.push(node.get("name").and_then(|n| n.as_str()).unwrap_or("Folder").to_string());
+            for child in node.get("children").and_then(|c| c.as_array()).into_iter().flatten() {
+                walk_chrome_node(child, folder, out);
+            }
+            folder.pop();
+        }
+        _ => {}
+    }
+}
+
+fn parse_chrome_json(json: &str) -> Result<Vec<Bookmark>, String> {
+    let root: Value = serde_json::from_str(json).map_err(|e| format!("Invalid Chrome bookmarks file: {}", e))?;
+    let roots = root.get("roots")
+        .and_then(|r| r.as_object())
+        .ok_or("Chrome bookmarks file has no \"roots\"")?;
+
+    let mut bookmarks = vec![];
+    for key in ["bookmark_bar", "other", "synced"] {
+        if let Some(node) = roots.get(key) {
+            walk_chrome_node(node, &mut vec![], &mut bookmarks);
+        }
+    }
+    Ok(bookmarks)
+}
+
+// ----- Firefox places.sqlite -----
+
+fn parse_firefox_places(path: &Path) -> Result<Vec<Bookmark>, String> {
+    // Firefox keeps places.sqlite locked while running, so always read from a copy
+    crate::read_database_copy(path, "places.sqlite", |conn| {
+        // type 1 = bookmark, 2 = folder. The "tags" root holds tag folders, not real bookmarks.
+        let mut folders: HashMap<i64, (i64, String, String)> = HashMap::new();
+        {
+            let mut stmt = conn.prepare("SELECT id, parent, COALESCE(title, ''), guid FROM moz_bookmarks WHERE type = 2")
+                .map_err(|e| format!("Not a Firefox places database: {}", e))?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?))
+            }).map_err(|e| e.to_string())?;
+            for (id, parent, title, guid) in rows.flatten() {
+                folders.insert(id, (parent, title, guid));
+            }
+        }
+
+        let folder_path = |mut id: i64| -> Option<Vec<String>> {
+            let mut path = vec![];
+            while let Some((parent, title, guid)) = folders.get(&id) {
+                match guid.as_str() {
+                    "tags________" => return None,
+                    "root________" => break,
+                    _ => path.push(title.clone()),
+                }
+                id = *parent;
+            }
+            path.reverse();
+            Some(path)
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT b.parent, COALESCE(b.title, p.title, ''), p.url, b.dateAdded
+             FROM moz_bookmarks b JOIN moz_places p ON p.id = b.fk
+             WHERE b.type = 1
+             ORDER BY b.parent, b.position"
+        ).map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, Option<i64>>(3)?))
+        }).map_err(|e| e.to_string())?;
+
+        let mut bookmarks = vec![];
+        for (parent, title, url, added) in rows.flatten() {
+            if let Some(folder) = folder_path(parent) {
+                bookmarks.push(Bookmark {
+                    folder,
+                    title,
+                    url,
+                    icon: None,
+                    added_at: added.and_then(|micros| format_timestamp(micros / 1_000_000)),
+                });
+            }
+        }
+        Ok(bookmarks)
+    })
+}
+
+// ----- Merge into the void -----
+
+pub(crate) fn folder_hub_url(folder: &[String]) -> String {
+    let mut url = url::Url::parse(BOOKMARK_HUB_PREFIX).expect("valid hub prefix");
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.pop_if_empty().extend(folder);
+    }
+    url.to_string()
+}
+
+fn dedup_key(url: &str) -> String {
+    url.trim_end_matches('/').to_string()
+}
+
+fn merge_bookmarks(conn: &mut Connection, bookmarks: Vec<Bookmark>, format: &str) -> Result<BookmarkImportStats, String> {
+    let mut stats = BookmarkImportStats {
+        format: format.to_string(),
+        bookmarks_found: bookmarks.len() as i32,
+        folders: 0,
+        nodes_imported: 0,
+        nodes_existing: 0,
+        bookmarks_skipped: 0,
+        edges_added: 0,
+    };
+
+    let mut existing: HashMap<String, PlacedNode> = HashMap::new();
+    {
+        let mut stmt = conn.prepare("SELECT id, url, position_x, position_y, position_z FROM nodes")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, (row.get(2)?, row.get(3)?, row.get(4)?)))
+        }).map_err(|e| e.to_string())?;
+        for (id, url, pos) in rows.flatten() {
+            existing.insert(dedup_key(&url), (id, pos));
+        }
+    }
+
+    // Every folder on the way down needs a hub, even ones that only contain sub-folders
+    let mut folder_paths: BTreeSet<Vec<String>> = BTreeSet::new();
+    folder_paths.insert(vec![]);
+    for bookmark in &bookmarks {
+        for depth in 1..=bookmark.folder.len() {
+            folder_paths.insert(bookmark.folder[..depth].to_vec());
+        }
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let add_edge = |source: i64, target: i64, stats: &mut BookmarkImportStats| -> Result<(), String> {
+        let added = tx.execute(
+            "INSERT OR IGNORE INTO edges (source_id, target_id) VALUES (?, ?)",
+            params![source, target],
+        ).map_err(|e| e.to_string())?;
+        stats.edges_added += added as i32;
+        Ok(())
+    };
+
+    // BTreeSet order guarantees a parent folder is handled before its children
+    let mut hubs: HashMap<Vec<String>, PlacedNode> = HashMap::new();
+    for folder in &folder_paths {
+        let hub_url = folder_hub_url(folder);
+        let hub = match existing.get(&dedup_key(&hub_url)) {
+            Some(&hub) => hub,
+            None => {
+                let pos = match folder.split_last().and_then(|(_, parent)| hubs.get(parent)) {
+                    // Push sub-folder hubs further out than bookmarks so clusters don't overlap
+                    Some(&(_, (x, y, z))) => {
+                        let (nx, ny, nz) = crate::generate_nearby_position(x, y, z);
+                        (x + (nx - x) * 2.5, y + (ny - y) * 2.5, z + (nz - z) * 2.5)
+                    }
+                    None => {
+                        let mut rng = rand::thread_rng();
+                        (rng.gen_range(-20.0..20.0), rng.gen_range(-15.0..15.0), rng.gen_range(-20.0..20.0))
+                    }
+                };
+                let title = folder.last().cloned().unwrap_or_else(|| "Bookmarks".to_string());
+                tx.execute(
+                    "INSERT INTO nodes (url, title, position_x, position_y, position_z, is_alive, last_crawled, created_at)
+                     VALUES (?, ?, ?, ?, ?, 1, datetime('now'), datetime('now'))",
+                    params![hub_url, title, pos.0, pos.1, pos.2],
+                ).map_err(|e| e.to_string())?;
+                let hub = (tx.last_insert_rowid(), pos);
+                existing.insert(dedup_key(&hub_url), hub);
+                hub
+            }
+        };
+        if let Some((_, parent)) = folder.split_last() {
+            if let Some(&(parent_id, _)) = hubs.get(parent) {
+                add_edge(parent_id, hub.0, &mut stats)?;
+            }
+        }
+        stats.folders += 1;
+        hubs.insert(folder.clone(), hub);
+    }
+    // The root hub isn't a real folder
+    stats.folders -= 1;
+
+    let mut seen_in_file: BTreeSet<(Vec<String>, String)> = BTreeSet::new();
+    for bookmark in bookmarks {
+        let is_web = bookmark.url.starts_with("http://") || bookmark.url.starts_with("https://");
+        let key = dedup_key(&bookmark.url);
+        if !is_web || !seen_in_file.insert((bookmark.folder.clone(), key.clone())) {
+            stats.bookmarks_skipped += 1;
+            continue;
+        }
+
+        let (hub_id, (hx, hy, hz)) = hubs[&bookmark.folder];
+        let node_id = match existing.get(&key) {
+            Some(&(id, _)) => {
+                stats.nodes_existing += 1;
+                id
+            }
+            None => {
+                let (x, y, z) = crate::generate_nearby_position(hx, hy, hz);
+                let title = if bookmark.title.is_empty() {
+                    url::Url::parse(&bookmark.url)
+                        .ok()
+                        .and_then(|u| u.host_str().map(|h| h.to_string()))
+                        .unwrap_or_else(|| bookmark.url.clone())
+                } else {
+                    bookmark.title.clone()
+                };
+                tx.execute(
+                    "INSERT INTO nodes (url, title, favicon, position_x, position_y, position_z, is_alive, created_at)
+                     VALUES (?, ?, ?, ?, ?, ?, 1, COALESCE(?, datetime('now')))",
+                    params![bookmark.url, title, bookmark.icon, x, y, z, bookmark.added_at],
+                ).map_err(|e| e.to_string())?;
+                let id = tx.last_insert_rowid();
+                existing.insert(key, (id, (x, y, z)));
+                stats.nodes_imported += 1;
+                id
+            }
+        };
+        add_edge(hub_id, node_id, &mut stats)?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(stats)
+}
+
+fn detect_format(path: &Path) -> &'static str {
+    let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    match ext.as_str() {
+        "html" | "htm" => "html",
+        "sqlite" | "db" => "firefox",
+        _ => "chrome",
+    }
+}
+
+/// Imports bookmarks into the active void. `format` is `html` (Netscape export),
+/// `chrome` (the `Bookmarks` file) or `firefox` (`places.sqlite`); detected from
+/// the file name when omitted.
+#[tauri::command]
+pub async fn import_bookmarks(
+    app: tauri::AppHandle,
+    path: String,
+    format: Option<String>,
+) -> Result<BookmarkImportStats, String> {
+    let file_path = Path::new(&path);
+    if !file_path.exists() {
+        return Err("Bookmarks file not found".to_string());
+    }
+
+    let format = format.map(|f| f.to_lowercase()).unwrap_or_else(|| detect_format(file_path).to_string());
+    let bookmarks = match format.as_str() {
+        "html" => {
+            let html = fs::read_to_string(file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+            parse_netscape_html(&html)
+        }
+        "chrome" => {
+            let json = fs::read_to_string(file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+            parse_chrome_json(&json)?
+        }
+        "firefox" => parse_firefox_places(file_path)?,
+        other => return Err(format!("Unknown bookmarks format '{}'. Use html, chrome or firefox.", other)),
+    };
+
+    let db_path = crate::get_active_db_path(&app)?;
+    let mut conn = crate::open_void_db(&db_path)?;
+
+    merge_bookmarks(&mut conn, bookmarks, &format)
+}
@@ -0,0 +1,801 @@
+// ============== PORTABLE .void BUNDLES ==============
+//
+// A `.void` file is a zip archive that carries everything needed to open a void
+// on another machine:
+//
+//   manifest.json           format + version, session metadata, sha256 of every file
+//   graph.json | void.db    the graph, either as JSON or as the raw session database;
+//                           graph.json also carries the backend's own tables row by row
+//   assets.json             which node each bundled thumbnail/favicon belongs to
+//   thumbnails/<id>.<ext>   node screenshots (pulled out of data URLs or local files)
+//   favicons/<id>.<ext>     node favicons (data URLs, local files, or fetched remotes)
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use base64::{Engine as _, engine::general_purpose};
+use rusqlite::{Connection, params, params_from_iter};
+use rusqlite::types::{Value, ValueRef};
+use sha2::{Digest, Sha256};
+use tauri::Manager;
+use zip::write::SimpleFileOptions;
+
+use crate::{VoidEdge, VoidNode, VOID_NODE_COLUMNS, void_node_from_row};
+
+pub const BUNDLE_FORMAT: &str = "void-bundle";
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const GRAPH_JSON_FILE: &str = "graph.json";
+const GRAPH_SQLITE_FILE: &str = "void.db";
+const ASSETS_FILE: &str = "assets.json";
+const THUMBNAILS_DIR: &str = "thumbnails";
+const FAVICONS_DIR: &str = "favicons";
+
+/// Void tables besides `nodes` and `edges` that graph.json carries, parents
+/// before the tables referencing them. `node_search` is rebuilt on import.
+const BUNDLE_TABLES: &[&str] = &[
+    "layouts", "layout_positions", "clusters", "node_metrics", "node_content",
+    "crawl_history", "crawl_log", "discovery_rules",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format: String,
+    pub format_version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    pub session: BundleSessionInfo,
+    /// Either `graph.json` or `void.db`
+    pub graph_file: String,
+    /// sha256 (hex) of every other file in the archive, keyed by archive path
+    pub files: BTreeMap<String, String>,
+    /// sha256 over the sorted `path:hash` lines of `files`
+    pub checksum: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleSessionInfo {
+    pub name: String,
+    pub node_count: i64,
+    pub edge_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BundleGraph {
+    nodes: Vec<VoidNode>,
+    edges: Vec<VoidEdge>,
+    /// Rows of each of `BUNDLE_TABLES`; absent in bundles from older versions
+    #[serde(default)]
+    tables: BTreeMap<String, BundleTable>,
+}
+
+/// A table's rows as JSON values in `columns` order. Blobs are stored as
+/// `{"base64": "..."}` so they can be told apart from text.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct BundleTable {
+    columns: Vec<String>,
+    rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// How the original column value was stored, so import can rebuild it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AssetOrigin {
+    /// `data:<mime>;base64,...` - rebuilt as the same data URL
+    DataUrl,
+    /// An absolute path on the exporting machine - extracted to the app data dir
+    LocalFile,
+    /// A remote URL - the column keeps the URL, the bytes are an offline copy
+    Remote,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BundleAsset {
+    file: String,
+    mime: String,
+    origin: AssetOrigin,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct NodeAssets {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    screenshot: Option<BundleAsset>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    favicon: Option<BundleAsset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleExportResult {
+    pub path: String,
+    pub node_count: i64,
+    pub edge_count: i64,
+    pub thumbnails: i32,
+    pub favicons: i32,
+    pub checksum: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleImportResult {
+    pub session_name: String,
+    pub path: String,
+    pub nodes_imported: i64,
+    pub edges_imported: i64,
+    pub assets_restored: i32,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn manifest_checksum(files: &BTreeMap<String, String>) -> String {
+    let lines: String = files.iter().map(|(path, hash)| format!("{}:{}\n", path, hash)).collect();
+    sha256_hex(lines.as_bytes())
+}
+
+fn extension_for_mime(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+        _ => "bin",
+    }
+}
+
+fn mime_for_path(path: &Path) -> String {
+    let ext = path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        _ => "application/octet-stream",
+    }.to_string()
+}
+
+fn parse_data_url(value: &str) -> Option<(String, Vec<u8>)> {
+    let rest = value.strip_prefix("data:")?;
+    let (header, data) = rest.split_once(',')?;
+    let mime = header.strip_suffix(";base64")?;
+    let bytes = general_purpose::STANDARD.decode(data).ok()?;
+    Some((mime.to_string(), bytes))
+}
+
+fn fetch_remote_asset(client: &reqwest::blocking::Client, url: &str) -> Option<(String, Vec<u8>)> {
+    let response = client.get(url).send().ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let mime = response.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(';').next().unwrap_or("").trim().to_string())
+        .filter(|v| v.starts_with("image/"))
+        .unwrap_or_else(|| mime_for_path(Path::new(url)));
+    let bytes = response.bytes().ok()?.to_vec();
+    if bytes.is_empty() {
+        return None;
+    }
+    Some((mime, bytes))
+}
+
+/// Resolves a screenshot/favicon column value to raw bytes, if it points at anything we can carry.
+fn load_asset(
+    value: &str,
+    client: Option<&reqwest::blocking::Client>,
+) -> Option<(AssetOrigin, String, Vec<u8>)> {
+    if value.starts_with("data:") {
+        return parse_data_url(value).map(|(mime, bytes)| (AssetOrigin::DataUrl, mime, bytes));
+    }
+    if value.starts_with("http://") || value.starts_with("https://") {
+        return client
+            .and_then(|c| fetch_remote_asset(c, value))
+            .map(|(mime, bytes)| (AssetOrigin::Remote, mime, bytes));
+    }
+    let path = Path::new(value);
+    if path.is_file() {
+        return fs::read(path).ok().map(|bytes| (AssetOrigin::LocalFile, mime_for_path(path), bytes));
+    }
+    None
+}
+
+fn json_from_sql(value: ValueRef) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => i.into(),
+        ValueRef::Real(f) => f.into(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t).into(),
+        ValueRef::Blob(b) => serde_json::json!({ "base64": general_purpose::STANDARD.encode(b) }),
+    }
+}
+
+fn sql_from_json(value: &serde_json::Value) -> Result<Value, String> {
+    Ok(match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        other => {
+            let encoded = other.get("base64").and_then(|b| b.as_str()).ok_or("Unsupported value in graph.json")?;
+            Value::Blob(general_purpose::STANDARD.decode(encoded).map_err(|e| e.to_string())?)
+        }
+    })
+}
+
+fn read_table(conn: &Connection, table: &str) -> Result<BundleTable, String> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {} ORDER BY rowid", table)).map_err(|e| e.to_string())?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+    let mut table = BundleTable { columns, rows: vec![] };
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let values = (0..table.columns.len())
+            .map(|i| row.get_ref(i).map(json_from_sql))
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?;
+        table.rows.push(values);
+    }
+    Ok(table)
+}
+
+/// Inserts `data` into `table`, skipping columns this version of the void
+/// does not have.
+fn write_table(conn: &Connection, table: &str, data: &BundleTable) -> Result<(), String> {
+    let known: Vec<String> = {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(1)).map_err(|e| e.to_string())?;
+        rows.flatten().collect()
+    };
+    let kept: Vec<usize> = (0..data.columns.len()).filter(|&i| known.contains(&data.columns[i])).collect();
+    if kept.is_empty() {
+        return Ok(());
+    }
+    let columns: Vec<&str> = kept.iter().map(|&i| data.columns[i].as_str()).collect();
+    let placeholders: Vec<String> = (1..=kept.len()).map(|i| format!("?{}", i)).collect();
+    let mut insert = conn.prepare(&format!(
+        "INSERT OR REPLACE INTO {} ({}) VALUES ({})",
+        table,
+        columns.join(", "),
+        placeholders.join(", ")
+    )).map_err(|e| e.to_string())?;
+    for row in &data.rows {
+        let values = kept.iter()
+            .map(|&i| sql_from_json(row.get(i).unwrap_or(&serde_json::Value::Null)))
+            .collect::<Result<Vec<_>, _>>()?;
+        insert.execute(params_from_iter(values)).map_err(|e| format!("Failed to import {}: {}", table, e))?;
+    }
+    Ok(())
+}
+
+struct BundleWriter {
+    zip: zip::ZipWriter<fs::File>,
+    files: BTreeMap<String, String>,
+}
+
+impl BundleWriter {
+    fn add(&mut self, name: &str, bytes: &[u8], compress: bool) -> Result<(), String> {
+        let method = if compress {
+            zip::CompressionMethod::Deflated
+        } else {
+            zip::CompressionMethod::Stored
+        };
+        let options = SimpleFileOptions::default().compression_method(method);
+        self.zip.start_file(name, options).map_err(|e| e.to_string())?;
+        self.zip.write_all(bytes).map_err(|e| e.to_string())?;
+        if name != MANIFEST_FILE {
+            self.files.insert(name.to_string(), sha256_hex(bytes));
+        }
+        Ok(())
+    }
+}
+
+fn export_bundle_blocking(
+    db_path: PathBuf,
+    session_name: String,
+    bundle_path: PathBuf,
+    graph_format: String,
+    include_favicons: bool,
+) -> Result<BundleExportResult, String> {
+    let conn = crate::open_void_db(&db_path)?;
+
+    let nodes: Vec<VoidNode> = {
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM nodes ORDER BY id", VOID_NODE_COLUMNS))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], void_node_from_row).map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+    };
+    let edges: Vec<VoidEdge> = {
+        let mut stmt = conn.prepare("SELECT id, source_id, target_id, edge_type FROM edges ORDER BY id")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| {
+            Ok(VoidEdge { id: row.get(0)?, source_id: row.get(1)?, target_id: row.get(2)?, edge_type: row.get(3)? })
+        }).map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+    };
+
+    let as_sqlite = match graph_format.as_str() {
+        "json" => false,
+        "sqlite" => true,
+        other => return Err(format!("Unknown graph format '{}'. Use 'json' or 'sqlite'.", other)),
+    };
+
+    let client = if include_favicons {
+        Some(
+            reqwest::blocking::Client::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+                .build()
+                .map_err(|e| e.to_string())?,
+        )
+    } else {
+        None
+    };
+
+    let file = fs::File::create(&bundle_path).map_err(|e| format!("Failed to create bundle: {}", e))?;
+    let mut writer = BundleWriter { zip: zip::ZipWriter::new(file), files: BTreeMap::new() };
+
+    let mut assets: BTreeMap<i64, NodeAssets> = BTreeMap::new();
+    let mut graph_nodes = Vec::with_capacity(nodes.len());
+    let mut remote_favicons: HashMap<String, BundleAsset> = HashMap::new();
+    let mut thumbnails = 0;
+    let mut favicons = 0;
+
+    for mut node in nodes {
+        let mut node_assets = NodeAssets::default();
+
+        // In SQLite mode data URLs already travel inside void.db, so only external references need bundling
+        if let Some(screenshot) = node.screenshot.clone() {
+            if !(as_sqlite && screenshot.starts_with("data:")) {
+                if let Some((origin, mime, bytes)) = load_asset(&screenshot, None) {
+                    let name = format!("{}/{}.{}", THUMBNAILS_DIR, node.id, extension_for_mime(&mime));
+                    writer.add(&name, &bytes, false)?;
+                    if !as_sqlite {
+                        node.screenshot = None;
+                    }
+                    node_assets.screenshot = Some(BundleAsset { file: name, mime, origin });
+                    thumbnails += 1;
+                }
+            }
+        }
+
+        if let Some(favicon) = node.favicon.clone() {
+            let is_remote = favicon.starts_with("http://") || favicon.starts_with("https://");
+            if let Some(existing) = remote_favicons.get(&favicon) {
+                node_assets.favicon = Some(existing.clone());
+            } else if !(as_sqlite && favicon.starts_with("data:")) {
+                if let Some((origin, mime, bytes)) = load_asset(&favicon, client.as_ref()) {
+                    let name = format!("{}/{}.{}", FAVICONS_DIR, node.id, extension_for_mime(&mime));
+                    writer.add(&name, &bytes, false)?;
+                    // Remote favicons keep their URL in the graph; the bundled bytes are an offline copy
+                    if !as_sqlite && !is_remote {
+                        node.favicon = None;
+                    }
+                    let asset = BundleAsset { file: name, mime, origin };
+                    if is_remote {
+                        remote_favicons.insert(favicon, asset.clone());
+                    }
+                    node_assets.favicon = Some(asset);
+                    favicons += 1;
+                }
+            }
+        }
+
+        if node_assets.screenshot.is_some() || node_assets.favicon.is_some() {
+            assets.insert(node.id, node_assets);
+        }
+        graph_nodes.push(node);
+    }
+
+    let node_count = graph_nodes.len() as i64;
+    let edge_count = edges.len() as i64;
+
+    let graph_file = if as_sqlite {
+        // VACUUM INTO gives a consistent, compacted copy even if the frontend has the DB open
+        let temp_path = std::env::temp_dir().join(format!(
+            "void-bundle-{}-{}.db",
+            std::process::id(),
+            chrono::Local::now().format("%Y%m%d%H%M%S%f")
+        ));
+        conn.execute("VACUUM INTO ?", params![temp_path.to_string_lossy().to_string()])
+            .map_err(|e| format!("Failed to snapshot void: {}", e))?;
+        let bytes = fs::read(&temp_path).map_err(|e| e.to_string());
+        fs::remove_file(&temp_path).ok();
+        writer.add(GRAPH_SQLITE_FILE, &bytes?, true)?;
+        GRAPH_SQLITE_FILE
+    } else {
+        let tables = BUNDLE_TABLES.iter()
+            .map(|&table| Ok((table.to_string(), read_table(&conn, table)?)))
+            .collect::<Result<_, String>>()?;
+        let graph = BundleGraph { nodes: graph_nodes, edges, tables };
+        let json = serde_json::to_vec(&graph).map_err(|e| e.to_string())?;
+        writer.add(GRAPH_JSON_FILE, &json, true)?;
+        GRAPH_JSON_FILE
+    };
+
+    let assets_json = serde_json::to_vec(&assets).map_err(|e| e.to_string())?;
+    writer.add(ASSETS_FILE, &assets_json, true)?;
+
+    let checksum = manifest_checksum(&writer.files);
+    let manifest = BundleManifest {
+        format: BUNDLE_FORMAT.to_string(),
+        format_version: BUNDLE_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        session: BundleSessionInfo { name: session_name, node_count, edge_count },
+        graph_file: graph_file.to_string(),
+        files: writer.files.clone(),
+        checksum: checksum.clone(),
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    writer.add(MANIFEST_FILE, &manifest_json, true)?;
+    writer.zip.finish().map_err(|e| format!("Failed to finish bundle: {}", e))?;
+
+    Ok(BundleExportResult {
+        path: bundle_path.to_string_lossy().to_string(),
+        node_count,
+        edge_count,
+        thumbnails,
+        favicons,
+        checksum,
+    })
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<fs::File>, name: &str) -> Result<Vec<u8>, String> {
+    let mut entry = archive.by_name(name).map_err(|_| format!("Bundle is missing '{}'", name))?;
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut bytes).map_err(|e| format!("Failed to read '{}': {}", name, e))?;
+    Ok(bytes)
+}
+
+fn open_bundle(path: &Path) -> Result<(zip::ZipArchive<fs::File>, BundleManifest), String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open bundle: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Not a valid .void bundle: {}", e))?;
+
+    let manifest_bytes = read_entry(&mut archive, MANIFEST_FILE)?;
+    let manifest: BundleManifest = serde_json::from_slice(&manifest_bytes)
+        .map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+
+    if manifest.format != BUNDLE_FORMAT {
+        return Err(format!("Unsupported bundle format '{}'", manifest.format));
+    }
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(format!(
+            "Bundle format version {} is newer than this app supports ({}). Please update Void Browser.",
+            manifest.format_version, BUNDLE_FORMAT_VERSION
+        ));
+    }
+    if manifest_checksum(&manifest.files) != manifest.checksum {
+        return Err("Bundle manifest checksum mismatch".to_string());
+    }
+
+    Ok((archive, manifest))
+}
+
+/// Whether `name` is one of the files a bundle may hold: the fixed top-level
+/// files or `thumbnails/<file>` and `favicons/<file>` with a plain file name.
+/// Anything else could point outside the folders it is extracted to.
+fn is_bundle_entry_name(name: &str) -> bool {
+    if [MANIFEST_FILE, GRAPH_JSON_FILE, GRAPH_SQLITE_FILE, ASSETS_FILE].contains(&name) {
+        return true;
+    }
+    let Some((dir, file)) = name.split_once('/') else {
+        return false;
+    };
+    let mut components = Path::new(file).components();
+    [THUMBNAILS_DIR, FAVICONS_DIR].contains(&dir)
+        && !file.contains(['\\', ':'])
+        && matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+}
+
+/// Reads and checksums a file listed in the manifest.
+fn read_verified(
+    archive: &mut zip::ZipArchive<fs::File>,
+    manifest: &BundleManifest,
+    name: &str,
+) -> Result<Vec<u8>, String> {
+    if !is_bundle_entry_name(name) {
+        return Err(format!("Bundle contains an invalid file name '{}'", name));
+    }
+    let expected = manifest.files.get(name)
+        .ok_or_else(|| format!("'{}' is not listed in the bundle manifest", name))?;
+    let bytes = read_entry(archive, name)?;
+    if sha256_hex(&bytes) != *expected {
+        return Err(format!("Checksum mismatch for '{}'. The bundle may be corrupted.", name));
+    }
+    Ok(bytes)
+}
+
+fn write_graph_json(conn: &mut Connection, graph: &BundleGraph) -> Result<(), String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    {
+        let mut insert_node = tx.prepare(
+            "INSERT INTO nodes (id, url, title, favicon, screenshot, position_x, position_y, position_z, is_alive, last_crawled, created_at, visit_count, last_visited, pinned, cluster_id, description, last_changed, last_status, last_error_class, site_depth)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)"
+        ).map_err(|e| e.to_string())?;
+        for node in &graph.nodes {
+            insert_node.execute(params![
+                node.id,
+                node.url,
+                node.title,
+                node.favicon,
+                node.screenshot,
+                node.position_x,
+                node.position_y,
+                node.position_z,
+                if node.is_alive { 1 } else { 0 },
+                node.last_crawled,
+                node.created_at,
+                node.visit_count,
+                node.last_visited,
+                if node.pinned { 1 } else { 0 },
+                node.cluster_id,
+                node.description,
+                node.last_changed,
+                node.last_status,
+                node.last_error_class,
+                node.site_depth,
+            ]).map_err(|e| format!("Failed to import node {}: {}", node.url, e))?;
+        }
+        // Aliases can point at nodes with higher ids, so link them once all exist
+        let mut set_canonical = tx.prepare("UPDATE nodes SET canonical_node_id = ?1 WHERE id = ?2")
+            .map_err(|e| e.to_string())?;
+        for node in graph.nodes.iter().filter(|n| n.canonical_node_id.is_some()) {
+            set_canonical.execute(params![node.canonical_node_id, node.id]).map_err(|e| e.to_string())?;
+        }
+
+        let mut insert_edge = tx.prepare(
+            "INSERT OR IGNORE INTO edges (id, source_id, target_id, edge_type) VALUES (?1, ?2, ?3, ?4)"
+        ).map_err(|e| e.to_string())?;
+        for edge in &graph.edges {
+            insert_edge.execute(params![edge.id, edge.source_id, edge.target_id, edge.edge_type])
+                .map_err(|e| e.to_string())?;
+        }
+
+        for &table in BUNDLE_TABLES {
+            if let Some(data) = graph.tables.get(table) {
+                write_table(&tx, table, data)?;
+            }
+        }
+        // Search bodies come from the latest content version
+        let mut with_content = tx.prepare("SELECT DISTINCT node_id FROM node_content").map_err(|e| e.to_string())?;
+        let node_ids: Vec<i64> = with_content.query_map([], |row| row.get(0)).map_err(|e| e.to_string())?.flatten().collect();
+        for node_id in node_ids {
+            if let Some(content) = crate::content::read_snapshot(&tx, node_id, None, false)? {
+                tx.execute("UPDATE node_search SET body = ?1 WHERE rowid = ?2", params![content.text, node_id])
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+fn import_bundle_blocking(
+    bundle_path: PathBuf,
+    session_db: PathBuf,
+    assets_dir: PathBuf,
+    inline_favicons: bool,
+) -> Result<(i64, i64, i32), String> {
+    let (mut archive, manifest) = open_bundle(&bundle_path)?;
+
+    // Verify every listed file up front so a corrupt bundle never leaves a half-imported session
+    for name in manifest.files.keys().cloned().collect::<Vec<_>>() {
+        read_verified(&mut archive, &manifest, &name)?;
+    }
+
+    let graph_bytes = read_verified(&mut archive, &manifest, &manifest.graph_file)?;
+    let assets: BTreeMap<i64, NodeAssets> = match manifest.files.contains_key(ASSETS_FILE) {
+        true => serde_json::from_slice(&read_verified(&mut archive, &manifest, ASSETS_FILE)?)
+            .map_err(|e| format!("Invalid assets.json: {}", e))?,
+        false => BTreeMap::new(),
+    };
+
+    let mut conn = if manifest.graph_file == GRAPH_SQLITE_FILE {
+        fs::write(&session_db, &graph_bytes).map_err(|e| format!("Failed to write session: {}", e))?;
+        let conn = Connection::open(&session_db).map_err(|e| e.to_string())?;
+        conn.query_row("SELECT COUNT(*) FROM nodes", [], |_| Ok(()))
+            .map_err(|e| format!("Bundle does not contain a valid session: {}", e))?;
+        conn
+    } else {
+        let graph: BundleGraph = serde_json::from_slice(&graph_bytes)
+            .map_err(|e| format!("Invalid graph.json: {}", e))?;
+        let mut conn = Connection::open(&session_db).map_err(|e| e.to_string())?;
+        crate::create_session_schema(&conn)?;
+        write_graph_json(&mut conn, &graph)?;
+        conn
+    };
+
+    let mut assets_restored = 0;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for (node_id, node_assets) in &assets {
+        for (column, dir, asset) in [
+            ("screenshot", THUMBNAILS_DIR, &node_assets.screenshot),
+            ("favicon", FAVICONS_DIR, &node_assets.favicon),
+        ] {
+            let Some(asset) = asset else { continue };
+            let bytes = read_verified(&mut archive, &manifest, &asset.file)?;
+
+            let value = match asset.origin {
+                AssetOrigin::DataUrl => {
+                    format!("data:{};base64,{}", asset.mime, general_purpose::STANDARD.encode(&bytes))
+                }
+                AssetOrigin::Remote if inline_favicons => {
+                    format!("data:{};base64,{}", asset.mime, general_purpose::STANDARD.encode(&bytes))
+                }
+                AssetOrigin::Remote => continue,
+                AssetOrigin::LocalFile => {
+                    // Named here rather than after the bundle's path, which is untrusted
+                    let folder = assets_dir.join(dir);
+                    fs::create_dir_all(&folder).map_err(|e| e.to_string())?;
+                    let target = folder.join(format!("{}.{}", node_id, extension_for_mime(&asset.mime)));
+                    fs::write(&target, &bytes).map_err(|e| e.to_string())?;
+                    target.to_string_lossy().to_string()
+                }
+            };
+
+            tx.execute(
+                &format!("UPDATE nodes SET {} = ?1 WHERE id = ?2", column),
+                params![value, node_id],
+            ).map_err(|e| e.to_string())?;
+            assets_restored += 1;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let node_count: i64 = conn.query_row("SELECT COUNT(*) FROM nodes", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let edge_count: i64 = conn.query_row("SELECT COUNT(*) FROM edges", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    Ok((node_count, edge_count, assets_restored))
+}
+
+/// Exports the active void as a shareable `.void` bundle.
+/// `graph_format` is `"json"` (default) or `"sqlite"`; `include_favicons` also downloads remote favicons.
+#[tauri::command]
+pub async fn export_void_bundle(
+    app: tauri::AppHandle,
+    path: String,
+    graph_format: Option<String>,
+    include_favicons: bool,
+) -> Result<BundleExportResult, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    if !db_path.exists() {
+        return Err("No active void to export.".to_string());
+    }
+
+    let session_name = crate::get_current_session(app.clone()).await?;
+    let graph_format = graph_format.unwrap_or_else(|| "json".to_string());
+    let bundle_path = PathBuf::from(&path);
+
+    std::thread::spawn(move || {
+        export_bundle_blocking(db_path, session_name, bundle_path, graph_format, include_favicons)
+    }).join().map_err(|_| "Thread panic")?
+}
+
+/// Reads a bundle's manifest without importing it, e.g. to preview it in the import dialog.
+#[tauri::command]
+pub async fn inspect_void_bundle(path: String) -> Result<BundleManifest, String> {
+    let (_, manifest) = open_bundle(Path::new(&path))?;
+    Ok(manifest)
+}
+
+/// Imports a `.void` bundle as a new saved session. Pass `session_name` to override the bundled name.
+/// With `inline_favicons`, remote favicons are replaced by the bundled copy so they work offline.
+#[tauri::command]
+pub async fn import_void_bundle(
+    app: tauri::AppHandle,
+    path: String,
+    session_name: Option<String>,
+    inline_favicons: bool,
+) -> Result<BundleImportResult, String> {
+    let bundle_path = PathBuf::from(&path);
+    let (_, manifest) = open_bundle(&bundle_path)?;
+
+    let name = session_name.unwrap_or(manifest.session.name);
+    crate::validate_session_name(&name)?;
+
+    let sessions_dir = crate::get_sessions_dir(&app)?;
+    let session_db = sessions_dir.join(format!("{}.db", name));
+    if session_db.exists() {
+        return Err(format!("Session '{}' already exists", name));
+    }
+
+    let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let assets_dir = app_data.join("bundle_assets").join(&name);
+
+    let target = session_db.clone();
+    let result = std::thread::spawn(move || {
+        import_bundle_blocking(bundle_path, target, assets_dir, inline_favicons)
+    }).join().map_err(|_| "Thread panic")?;
+
+    match result {
+        Ok((nodes_imported, edges_imported, assets_restored)) => Ok(BundleImportResult {
+            session_name: name,
+            path: session_db.to_string_lossy().to_string(),
+            nodes_imported,
+            edges_imported,
+            assets_restored,
+        }),
+        Err(e) => {
+            fs::remove_file(&session_db).ok();
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a JSON bundle whose only asset claims to live at `asset_file`.
+    fn write_bundle(path: &Path, asset_file: &str) {
+        let file = fs::File::create(path).unwrap();
+        let mut writer = BundleWriter { zip: zip::ZipWriter::new(file), files: BTreeMap::new() };
+
+        let graph = BundleGraph { nodes: vec![], edges: vec![], tables: BTreeMap::new() };
+        writer.add(GRAPH_JSON_FILE, &serde_json::to_vec(&graph).unwrap(), true).unwrap();
+        writer.add(asset_file, b"not really a png", false).unwrap();
+        let mut assets = BTreeMap::new();
+        assets.insert(1i64, NodeAssets {
+            screenshot: Some(BundleAsset { file: asset_file.to_string(), mime: "image/png".to_string(), origin: AssetOrigin::LocalFile }),
+            favicon: None,
+        });
+        writer.add(ASSETS_FILE, &serde_json::to_vec(&assets).unwrap(), true).unwrap();
+
+        let checksum = manifest_checksum(&writer.files);
+        let manifest = BundleManifest {
+            format: BUNDLE_FORMAT.to_string(),
+            format_version: BUNDLE_FORMAT_VERSION,
+            app_version: "test".to_string(),
+            created_at: String::new(),
+            session: BundleSessionInfo { name: "test".to_string(), node_count: 0, edge_count: 0 },
+            graph_file: GRAPH_JSON_FILE.to_string(),
+            files: writer.files.clone(),
+            checksum,
+        };
+        writer.add(MANIFEST_FILE, &serde_json::to_vec(&manifest).unwrap(), true).unwrap();
+        writer.zip.finish().unwrap();
+    }
+
+    #[test]
+    fn rejects_asset_paths_outside_the_asset_folders() {
+        let dir = std::env::temp_dir().join(format!("void-bundle-test-{}", std::process::id()));
+        let assets_dir = dir.join("assets");
+        fs::create_dir_all(&assets_dir).unwrap();
+        let escaped = dir.join("escaped.png");
+
+        for asset_file in ["../escaped.png".to_string(), "thumbnails/../../escaped.png".to_string(), escaped.to_string_lossy().to_string()] {
+            let bundle = dir.join("evil.void");
+            let session_db = dir.join("session.db");
+            write_bundle(&bundle, &asset_file);
+
+            let result = import_bundle_blocking(bundle, session_db.clone(), assets_dir.clone(), false);
+            assert!(result.is_err(), "bundle with asset '{}' was imported", asset_file);
+            assert!(!escaped.exists(), "asset '{}' was written outside the asset folder", asset_file);
+            fs::remove_file(&session_db).ok();
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn accepts_plain_asset_names() {
+        assert!(is_bundle_entry_name("thumbnails/12.png"));
+        assert!(is_bundle_entry_name("favicons/3.ico"));
+        assert!(is_bundle_entry_name(GRAPH_JSON_FILE));
+        assert!(!is_bundle_entry_name("thumbnails/a/b.png"));
+        assert!(!is_bundle_entry_name("thumbnails/.."));
+        assert!(!is_bundle_entry_name("other/1.png"));
+    }
+}
@@ -0,0 +1,189 @@
+// ============== CHANGE DETECTION ==============
+//
+// Every successful crawl adds a `crawl_history` row with a SHA-256 of the
+// page's readable text and a 64-bit simhash of its word shingles. Comparing
+// with the previous row tells whether the page changed and by how much, and
+// `nodes.last_changed` records when it last did.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::{VoidNode, VOID_NODE_COLUMNS, void_node_from_row};
+
+/// Words per shingle for the simhash.
+const SHINGLE_WORDS: usize = 3;
+const DEFAULT_CHANGE_LIMIT: i64 = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlChange {
+    /// False on a node's first recorded crawl
+    pub changed: bool,
+    /// Simhash similarity to the previous crawl, 0..1
+    pub similarity: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlHistoryEntry {
+    pub crawled_at: String,
+    pub title: Option<String>,
+    pub content_hash: String,
+    pub text_length: i64,
+    pub changed: bool,
+    pub similarity: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedNode {
+    pub node: VoidNode,
+    /// Crawls since the cutoff that found new content
+    pub change_count: i32,
+    /// Similarity at the most recent change; low means a rewrite
+    pub last_similarity: Option<f64>,
+}
+
+/// 64-bit FNV-1a; stable across runs and platforms, unlike `DefaultHasher`.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Charikar simhash over overlapping word shingles. Near-identical texts get
+/// hashes that differ in few bits.
+pub(crate) fn simhash(text: &str) -> u64 {
+    let words: Vec<String> = text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+    if words.is_empty() {
+        return 0;
+    }
+
+    let mut weights = [0i64; 64];
+    for shingle in words.windows(SHINGLE_WORDS.min(words.len())) {
+        let hash = fnv1a(&shingle.join(" "));
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            *weight += if hash >> bit & 1 == 1 { 1 } else { -1 };
+        }
+    }
+    weights.iter().enumerate()
+        .filter(|(_, &w)| w > 0)
+        .fold(0u64, |hash, (bit, _)| hash | 1 << bit)
+}
+
+pub(crate) fn simhash_similarity(a: u64, b: u64) -> f64 {
+    1.0 - (a ^ b).count_ones() as f64 / 64.0
+}
+
+pub(crate) fn content_hash(text: &str) -> String {
+    Sha256::digest(text.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Adds a `crawl_history` row for a successful crawl of `node_id` and
+/// compares it with the previous one.
+pub(crate) fn record_crawl(conn: &Connection, node_id: i64, title: Option<&str>, text: &str) -> Result<CrawlChange, String> {
+    let hash = content_hash(text);
+    let simhash = simhash(text);
+
+    let previous: Option<(String, i64)> = conn.query_row(
+        "SELECT content_hash, simhash FROM crawl_history WHERE node_id = ?1 ORDER BY id DESC LIMIT 1",
+        params![node_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional().map_err(|e| e.to_string())?;
+
+    let change = match previous {
+        Some((previous_hash, previous_simhash)) => CrawlChange {
+            changed: previous_hash != hash,
+            // Stored as i64 since SQLite integers are signed
+            similarity: Some(simhash_similarity(previous_simhash as u64, simhash)),
+        },
+        None => CrawlChange { changed: false, similarity: None },
+    };
+
+    conn.execute(
+        "INSERT INTO crawl_history (node_id, crawled_at, title, content_hash, simhash, text_length, changed, similarity)
+         VALUES (?1, datetime('now'), ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            node_id,
+            title,
+            hash,
+            simhash as i64,
+            text.chars().count() as i64,
+            if change.changed { 1 } else { 0 },
+            change.similarity,
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    if change.changed {
+        conn.execute("UPDATE nodes SET last_changed = datetime('now') WHERE id = ?1", params![node_id])
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(change)
+}
+
+/// Nodes whose content changed at or after `changed_since` (`YYYY-MM-DD` or
+/// `YYYY-MM-DD HH:MM:SS`), most recently changed first.
+#[tauri::command]
+pub async fn list_changed_nodes(
+    app: tauri::AppHandle,
+    changed_since: String,
+    limit: Option<i64>,
+) -> Result<Vec<ChangedNode>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+
+    let since: Option<String> = conn.query_row("SELECT datetime(?1)", params![changed_since.trim()], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let since = since.ok_or_else(|| format!("Invalid date '{}'. Use YYYY-MM-DD.", changed_since))?;
+
+    let columns = crate::void_node_columns_as("n");
+    let extra = VOID_NODE_COLUMNS.split(", ").count();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, COUNT(*),
+                (SELECT similarity FROM crawl_history
+                 WHERE node_id = n.id AND changed = 1 ORDER BY id DESC LIMIT 1)
+         FROM crawl_history h
+         JOIN nodes n ON n.id = h.node_id
+         WHERE h.changed = 1 AND h.crawled_at >= ?1
+         GROUP BY n.id
+         ORDER BY MAX(h.crawled_at) DESC
+         LIMIT ?2",
+        columns
+    )).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![since, limit.unwrap_or(DEFAULT_CHANGE_LIMIT)], |row| {
+        Ok(ChangedNode {
+            node: void_node_from_row(row)?,
+            change_count: row.get(extra)?,
+            last_similarity: row.get(extra + 1)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+/// Every recorded crawl of a node, newest first.
+#[tauri::command]
+pub async fn get_change_history(
+    app: tauri::AppHandle,
+    node_id: i64,
+    limit: Option<i64>,
+) -> Result<Vec<CrawlHistoryEntry>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT crawled_at, title, content_hash, text_length, changed, similarity
+         FROM crawl_history WHERE node_id = ?1 ORDER BY id DESC LIMIT ?2"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![node_id, limit.unwrap_or(DEFAULT_CHANGE_LIMIT)], |row| {
+        Ok(CrawlHistoryEntry {
+            crawled_at: row.get(0)?,
+            title: row.get(1)?,
+            content_hash: row.get(2)?,
+            text_length: row.get(3)?,
+            changed: row.get::<_, i32>(4)? == 1,
+            similarity: row.get(5)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
@@ -0,0 +1,371 @@
+// ============== COMMUNITY DETECTION ==============
+//
+// Louvain modularity clustering over the link graph (edges are treated as
+// undirected). Each community gets a `cluster_id` on its nodes and a row in
+// `clusters` with a label taken from its dominant domain, or from title
+// words that are common in the cluster but rare elsewhere.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use rusqlite::{Connection, params};
+
+/// Words that say nothing about what a cluster is about.
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "from", "that", "this", "your", "you", "are", "was", "our",
+    "home", "page", "welcome", "official", "site", "website", "online", "free", "best", "new",
+    "www", "com", "org", "net", "html", "index", "untitled", "about", "how", "what", "all",
+];
+
+/// Share of a cluster that must come from one domain for the domain to name it.
+const DOMAIN_LABEL_SHARE: f64 = 0.5;
+const MAX_LOUVAIN_PASSES: usize = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterInfo {
+    pub id: i64,
+    pub label: String,
+    pub size: i32,
+    pub top_domain: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterResult {
+    pub cluster_count: i32,
+    pub modularity: f64,
+    /// Nodes without any edges; their `cluster_id` is cleared
+    pub unclustered: i32,
+    /// Largest first
+    pub clusters: Vec<ClusterInfo>,
+}
+
+// ----- Louvain -----
+
+/// Undirected weighted graph. `adjacency` holds no self-loops; those live in
+/// `loops` so that aggregated communities keep their internal weight.
+#[derive(Clone)]
+struct WeightedGraph {
+    adjacency: Vec<Vec<(usize, f64)>>,
+    loops: Vec<f64>,
+}
+
+impl WeightedGraph {
+    fn degree(&self, i: usize) -> f64 {
+        self.adjacency[i].iter().map(|&(_, w)| w).sum::<f64>() + 2.0 * self.loops[i]
+    }
+
+    /// Moves single nodes to the neighboring community with the best modularity
+    /// gain until nothing moves. Returns dense community ids and whether any
+    /// node changed community.
+    fn local_moves(&self, resolution: f64) -> (Vec<usize>, bool) {
+        let n = self.adjacency.len();
+        let degrees: Vec<f64> = (0..n).map(|i| self.degree(i)).collect();
+        let total: f64 = degrees.iter().sum();
+        let mut community: Vec<usize> = (0..n).collect();
+        let mut community_degree = degrees.clone();
+        if total == 0.0 {
+            return (community, false);
+        }
+
+        let mut weight_to: Vec<f64> = vec![0.0; n];
+        let mut touched: Vec<usize> = vec![];
+        let mut improved = false;
+
+        for _ in 0..MAX_LOUVAIN_PASSES {
+            let mut moved = false;
+            for i in 0..n {
+                let current = community[i];
+                for &(j, w) in &self.adjacency[i] {
+                    let c = community[j];
+                    if weight_to[c] == 0.0 {
+                        touched.push(c);
+                    }
+                    weight_to[c] += w;
+                }
+
+                community_degree[current] -= degrees[i];
+                let gain = |c: usize, weight: f64| weight - resolution * community_degree[c] * degrees[i] / total;
+                let mut best = current;
+                let mut best_gain = gain(current, weight_to[current]);
+                for &c in &touched {
+                    let g = gain(c, weight_to[c]);
+                    if g > best_gain + 1e-12 {
+                        best = c;
+                        best_gain = g;
+                    }
+                }
+                community_degree[best] += degrees[i];
+
+                if best != current {
+                    community[i] = best;
+                    moved = true;
+                    improved = true;
+                }
+                for c in touched.drain(..) {
+                    weight_to[c] = 0.0;
+                }
+            }
+            if !moved {
+                break;
+            }
+        }
+
+        // Renumber to 0..k in order of first appearance
+        let mut dense: HashMap<usize, usize> = HashMap::new();
+        for c in community.iter_mut() {
+            let next = dense.len();
+            *c = *dense.entry(*c).or_insert(next);
+        }
+        (community, improved)
+    }
+
+    /// Collapses every community into one node.
+    fn aggregate(&self, community: &[usize]) -> WeightedGraph {
+        let count = community.iter().max().map(|&c| c + 1).unwrap_or(0);
+        let mut weights: Vec<HashMap<usize, f64>> = vec![HashMap::new(); count];
+        let mut loops = vec![0.0; count];
+
+        for (i, neighbors) in self.adjacency.iter().enumerate() {
+            let ci = community[i];
+            loops[ci] += self.loops[i];
+            for &(j, w) in neighbors {
+                let cj = community[j];
+                if ci == cj {
+                    // Each internal edge is seen from both ends
+                    loops[ci] += w / 2.0;
+                } else {
+                    *weights[ci].entry(cj).or_insert(0.0) += w;
+                }
+            }
+        }
+
+        WeightedGraph {
+            adjacency: weights.into_iter().map(|m| {
+                let mut list: Vec<(usize, f64)> = m.into_iter().collect();
+                list.sort_by_key(|&(j, _)| j);
+                list
+            }).collect(),
+            loops,
+        }
+    }
+
+    fn modularity(&self, community: &[usize], resolution: f64) -> f64 {
+        let count = community.iter().max().map(|&c| c + 1).unwrap_or(0);
+        let mut internal = vec![0.0; count];
+        let mut degree = vec![0.0; count];
+        for (i, neighbors) in self.adjacency.iter().enumerate() {
+            let ci = community[i];
+            degree[ci] += self.degree(i);
+            internal[ci] += self.loops[i];
+            for &(j, w) in neighbors {
+                if community[j] == ci {
+                    internal[ci] += w / 2.0;
+                }
+            }
+        }
+        let total: f64 = degree.iter().sum();
+        if total == 0.0 {
+            return 0.0;
+        }
+        (0..count)
+            .map(|c| 2.0 * internal[c] / total - resolution * (degree[c] / total).powi(2))
+            .sum()
+    }
+}
+
+/// Runs Louvain to convergence and returns each node's community.
+fn louvain(graph: &WeightedGraph, resolution: f64) -> Vec<usize> {
+    let mut partition: Vec<usize> = (0..graph.adjacency.len()).collect();
+    let mut level = graph.clone();
+
+    loop {
+        let (community, improved) = level.local_moves(resolution);
+        if !improved {
+            break;
+        }
+        for c in partition.iter_mut() {
+            *c = community[*c];
+        }
+        level = level.aggregate(&community);
+    }
+    partition
+}
+
+// ----- Labels -----
+
+fn domain_of(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_lowercase()))
+}
+
+fn title_terms(title: &str) -> HashSet<String> {
+    title.split(|c: char| !c.is_alphanumeric())
+        .map(|w| w.to_lowercase())
+        .filter(|w| w.chars().count() >= 3 && !w.chars().all(|c| c.is_ascii_digit()))
+        .filter(|w| !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Names a cluster after its dominant domain, or else the title words that
+/// best separate it from the rest of the void (document frequency weighted by
+/// inverse global frequency).
+fn cluster_label(
+    members: &[usize],
+    domains: &[Option<String>],
+    terms: &[HashSet<String>],
+    global_frequency: &HashMap<&str, usize>,
+    node_count: usize,
+) -> (String, Option<String>) {
+    let mut domain_counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for &i in members {
+        if let Some(domain) = &domains[i] {
+            *domain_counts.entry(domain.as_str()).or_insert(0) += 1;
+        }
+    }
+    let top_domain = domain_counts.iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(d, &count)| (d.to_string(), count));
+
+    if let Some((domain, count)) = &top_domain {
+        if *count as f64 >= members.len() as f64 * DOMAIN_LABEL_SHARE {
+            let others = domain_counts.len() - 1;
+            let label = if others > 0 { format!("{} +{}", domain, others) } else { domain.clone() };
+            return (label, Some(domain.clone()));
+        }
+    }
+
+    let mut term_counts: HashMap<&str, usize> = HashMap::new();
+    for &i in members {
+        for term in &terms[i] {
+            *term_counts.entry(term.as_str()).or_insert(0) += 1;
+        }
+    }
+    let min_count = 2.max(members.len() / 10);
+    let mut scored: Vec<(&str, f64)> = term_counts.into_iter()
+        .filter(|&(_, count)| count >= min_count)
+        .map(|(term, count)| {
+            let global = global_frequency.get(term).copied().unwrap_or(1) as f64;
+            (term, count as f64 * (node_count as f64 / global).ln())
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    let top_domain = top_domain.map(|(d, _)| d);
+    if scored.is_empty() {
+        let label = top_domain.clone().unwrap_or_else(|| "Cluster".to_string());
+        return (label, top_domain);
+    }
+    let label = scored.iter().take(3).map(|(t, _)| *t).collect::<Vec<_>>().join(" · ");
+    (label, top_domain)
+}
+
+// ----- Command -----
+
+pub(crate) fn compute_clusters_in(conn: &mut Connection, resolution: f64) -> Result<ClusterResult, String> {
+    let mut ids: Vec<i64> = vec![];
+    let mut domains: Vec<Option<String>> = vec![];
+    let mut terms: Vec<HashSet<String>> = vec![];
+    {
+        let mut stmt = conn.prepare("SELECT id, url, title FROM nodes ORDER BY id").map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+        }).map_err(|e| e.to_string())?;
+        for (id, url, title) in rows.flatten() {
+            ids.push(id);
+            domains.push(domain_of(&url));
+            terms.push(title_terms(title.as_deref().unwrap_or("")));
+        }
+    }
+    let index: HashMap<i64, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+
+    let mut weights: Vec<HashMap<usize, f64>> = vec![HashMap::new(); ids.len()];
+    {
+        let mut stmt = conn.prepare("SELECT source_id, target_id FROM edges").map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))
+            .map_err(|e| e.to_string())?;
+        for (source, target) in rows.flatten() {
+            if let (Some(&a), Some(&b)) = (index.get(&source), index.get(&target)) {
+                if a != b {
+                    // Links in both directions make a stronger tie
+                    *weights[a].entry(b).or_insert(0.0) += 1.0;
+                    *weights[b].entry(a).or_insert(0.0) += 1.0;
+                }
+            }
+        }
+    }
+    let graph = WeightedGraph {
+        adjacency: weights.into_iter().map(|m| {
+            let mut list: Vec<(usize, f64)> = m.into_iter().collect();
+            list.sort_by_key(|&(j, _)| j);
+            list
+        }).collect(),
+        loops: vec![0.0; ids.len()],
+    };
+
+    let partition = louvain(&graph, resolution);
+    let modularity = graph.modularity(&partition, resolution);
+
+    let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (i, &c) in partition.iter().enumerate() {
+        if !graph.adjacency[i].is_empty() {
+            members.entry(c).or_default().push(i);
+        }
+    }
+    let unclustered = ids.len() - members.values().map(|m| m.len()).sum::<usize>();
+    let mut members: Vec<Vec<usize>> = members.into_values().collect();
+    members.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+
+    let mut global_frequency: HashMap<&str, usize> = HashMap::new();
+    for set in &terms {
+        for term in set {
+            *global_frequency.entry(term.as_str()).or_insert(0) += 1;
+        }
+    }
+
+    let clusters: Vec<ClusterInfo> = members.iter()
+        .enumerate()
+        .map(|(id, nodes)| {
+            let (label, top_domain) = cluster_label(nodes, &domains, &terms, &global_frequency, ids.len());
+            ClusterInfo { id: id as i64, label, size: nodes.len() as i32, top_domain }
+        })
+        .collect();
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute("UPDATE nodes SET cluster_id = NULL", []).map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM clusters", []).map_err(|e| e.to_string())?;
+    {
+        let mut insert_cluster = tx.prepare(
+            "INSERT INTO clusters (id, label, size, top_domain, computed_at) VALUES (?1, ?2, ?3, ?4, datetime('now'))"
+        ).map_err(|e| e.to_string())?;
+        let mut assign = tx.prepare("UPDATE nodes SET cluster_id = ?1 WHERE id = ?2").map_err(|e| e.to_string())?;
+        for (cluster, nodes) in clusters.iter().zip(&members) {
+            insert_cluster.execute(params![cluster.id, cluster.label, cluster.size, cluster.top_domain])
+                .map_err(|e| e.to_string())?;
+            for &i in nodes {
+                assign.execute(params![cluster.id, ids[i]]).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(ClusterResult {
+        cluster_count: clusters.len() as i32,
+        modularity,
+        unclustered: unclustered as i32,
+        clusters,
+    })
+}
+
+/// Detects communities in the active void with Louvain and stores a
+/// `cluster_id` on every linked node. `resolution` above 1 gives more, smaller
+/// clusters; below 1 fewer, larger ones.
+#[tauri::command]
+pub async fn compute_clusters(app: tauri::AppHandle, resolution: Option<f64>) -> Result<ClusterResult, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let resolution = resolution.filter(|r| *r > 0.0).unwrap_or(1.0);
+
+    std::thread::spawn(move || {
+        let mut conn = crate::open_void_db(&db_path)?;
+        compute_clusters_in(&mut conn, resolution)
+    }).join().map_err(|_| "Thread panic")?
+}
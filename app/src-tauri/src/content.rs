@@ -0,0 +1,270 @@
+// ============== PAGE CONTENT SNAPSHOTS ==============
+//
+// With `save_content`, `crawl_single_node` keeps a gzip-compressed copy of
+// the page's readable text (navigation, scripts and other boilerplate
+// stripped) and of its raw HTML, one numbered version per crawl. The latest
+// text is also fed to the search index, so the void stays searchable and
+// readable after a site goes dead.
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use rusqlite::{Connection, OptionalExtension, params};
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// Older versions beyond this many per node are dropped.
+const MAX_CONTENT_VERSIONS: i64 = 20;
+/// Raw HTML above this size is not kept; the text still is.
+const MAX_SNAPSHOT_HTML_BYTES: usize = 5 * 1024 * 1024;
+/// Short blocks made mostly of link text are menus, not content.
+const NAV_BLOCK_MAX_CHARS: usize = 200;
+
+const SKIPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "object", "embed",
+    "nav", "header", "footer", "aside", "form", "button", "select", "input", "textarea", "dialog",
+];
+
+const BLOCK_TAGS: &[&str] = &[
+    "p", "div", "section", "article", "main", "h1", "h2", "h3", "h4", "h5", "h6", "li", "ul", "ol",
+    "dl", "dt", "dd", "pre", "blockquote", "table", "tr", "td", "th", "figcaption", "br", "hr",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentVersion {
+    pub version: i64,
+    pub fetched_at: String,
+    pub title: Option<String>,
+    pub text_length: i64,
+    pub has_html: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeContent {
+    pub node_id: i64,
+    pub version: i64,
+    pub fetched_at: String,
+    pub title: Option<String>,
+    pub text: String,
+    /// Only filled when asked for with `include_html`
+    pub html: Option<String>,
+    pub version_count: i64,
+}
+
+// ----- Text extraction -----
+
+/// Collects text into blocks, tracking how much of each block is link text.
+#[derive(Default)]
+struct TextCollector {
+    blocks: Vec<String>,
+    current: String,
+    link_chars: usize,
+    link_depth: usize,
+}
+
+impl TextCollector {
+    fn push_text(&mut self, text: &str) {
+        if self.link_depth > 0 {
+            self.link_chars += text.trim().chars().count();
+        }
+        self.current.push_str(text);
+    }
+
+    fn flush(&mut self) {
+        let text = self.current.split_whitespace().collect::<Vec<_>>().join(" ");
+        let length = text.chars().count();
+        let is_navigation = length < NAV_BLOCK_MAX_CHARS && self.link_chars * 2 > length;
+        if length > 0 && !is_navigation && self.blocks.last() != Some(&text) {
+            self.blocks.push(text);
+        }
+        self.current.clear();
+        self.link_chars = 0;
+    }
+
+    fn walk(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.push_text(text),
+                Node::Element(el) => {
+                    let name = el.name();
+                    if SKIPPED_TAGS.contains(&name) || el.attr("hidden").is_some() || el.attr("aria-hidden") == Some("true") {
+                        continue;
+                    }
+                    let Some(child) = ElementRef::wrap(child) else {
+                        continue;
+                    };
+                    let is_block = BLOCK_TAGS.contains(&name);
+                    let is_link = name == "a";
+                    if is_block {
+                        self.flush();
+                    }
+                    if is_link {
+                        self.link_depth += 1;
+                    }
+                    self.walk(child);
+                    if is_link {
+                        self.link_depth -= 1;
+                    }
+                    if is_block {
+                        self.flush();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn collect_text(root: ElementRef) -> String {
+    let mut collector = TextCollector::default();
+    collector.walk(root);
+    collector.flush();
+    collector.blocks.join("\n\n")
+}
+
+/// Readable text of a page: the `<article>` or `<main>` element when it has
+/// real content, otherwise the whole body, minus navigation and scripts.
+/// Paragraphs are separated by blank lines.
+pub(crate) fn extract_readable_text(document: &Html) -> String {
+    for selector in ["article", "main", r#"[role="main"]"#] {
+        let Ok(selector) = Selector::parse(selector) else {
+            continue;
+        };
+        if let Some(root) = document.select(&selector).next() {
+            let text = collect_text(root);
+            if text.chars().count() >= NAV_BLOCK_MAX_CHARS {
+                return text;
+            }
+        }
+    }
+    let body = Selector::parse("body").ok().and_then(|s| document.select(&s).next());
+    collect_text(body.unwrap_or_else(|| document.root_element()))
+}
+
+// ----- Storage -----
+
+fn compress(data: &str) -> Result<Vec<u8>, String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data.as_bytes()).map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())
+}
+
+fn decompress(data: &[u8]) -> Result<String, String> {
+    let mut text = String::new();
+    GzDecoder::new(data).read_to_string(&mut text).map_err(|e| format!("Corrupt snapshot: {}", e))?;
+    Ok(text)
+}
+
+/// Stores a new content version for `node_id` from its raw HTML and the
+/// text `extract_readable_text` got from it, updates the search index with the
+/// text and prunes old versions. Returns the new version number.
+pub(crate) fn store_snapshot(
+    conn: &Connection,
+    node_id: i64,
+    title: Option<&str>,
+    html: &str,
+    text: &str,
+) -> Result<i64, String> {
+    let html_gz = if html.len() <= MAX_SNAPSHOT_HTML_BYTES { Some(compress(html)?) } else { None };
+
+    let version: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) + 1 FROM node_content WHERE node_id = ?1",
+        params![node_id],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO node_content (node_id, version, title, text_gz, html_gz, text_length, html_length, fetched_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now'))",
+        params![node_id, version, title, compress(text)?, html_gz, text.chars().count() as i64, html.len() as i64],
+    ).map_err(|e| e.to_string())?;
+
+    conn.execute("UPDATE node_search SET body = ?1 WHERE rowid = ?2", params![text, node_id])
+        .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM node_content WHERE node_id = ?1 AND version <= ?2",
+        params![node_id, version - MAX_CONTENT_VERSIONS],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(version)
+}
+
+pub(crate) fn read_snapshot(
+    conn: &Connection,
+    node_id: i64,
+    version: Option<i64>,
+    include_html: bool,
+) -> Result<Option<NodeContent>, String> {
+    let row = conn.query_row(
+        "SELECT version, fetched_at, title, text_gz, html_gz FROM node_content
+         WHERE node_id = ?1 AND (?2 IS NULL OR version = ?2)
+         ORDER BY version DESC LIMIT 1",
+        params![node_id, version],
+        |row| Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Vec<u8>>(3)?,
+            row.get::<_, Option<Vec<u8>>>(4)?,
+        )),
+    ).optional().map_err(|e| e.to_string())?;
+    let Some((version, fetched_at, title, text_gz, html_gz)) = row else {
+        return Ok(None);
+    };
+
+    let version_count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM node_content WHERE node_id = ?1",
+        params![node_id],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+
+    let html = match html_gz {
+        Some(data) if include_html => Some(decompress(&data)?),
+        _ => None,
+    };
+
+    Ok(Some(NodeContent {
+        node_id,
+        version,
+        fetched_at,
+        title,
+        text: decompress(&text_gz)?,
+        html,
+        version_count,
+    }))
+}
+
+/// The saved text of a node, latest version unless `version` is given. Raw
+/// HTML is included with `include_html`. `None` if nothing was saved.
+#[tauri::command]
+pub async fn get_node_content(
+    app: tauri::AppHandle,
+    node_id: i64,
+    version: Option<i64>,
+    include_html: Option<bool>,
+) -> Result<Option<NodeContent>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+    read_snapshot(&conn, node_id, version, include_html.unwrap_or(false))
+}
+
+/// Saved content versions of a node, newest first.
+#[tauri::command]
+pub async fn list_node_content(app: tauri::AppHandle, node_id: i64) -> Result<Vec<ContentVersion>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT version, fetched_at, title, text_length, html_gz IS NOT NULL FROM node_content
+         WHERE node_id = ?1 ORDER BY version DESC"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![node_id], |row| {
+        Ok(ContentVersion {
+            version: row.get(0)?,
+            fetched_at: row.get(1)?,
+            title: row.get(2)?,
+            text_length: row.get(3)?,
+            has_html: row.get(4)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
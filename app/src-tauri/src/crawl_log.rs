@@ -0,0 +1,150 @@
+// ============== CRAWL LOG ==============
+//
+// One `crawl_log` row per fetch, successful or not: HTTP status, the URL the
+// redirects ended on and the hops before it, latency, body size and an error
+// class, so a timeout, a 404 and a 500 can be told apart. The latest status
+// and error class are also kept on the node itself.
+
+use serde::{Deserialize, Serialize};
+use rusqlite::{Connection, params};
+
+use crate::PageMetadata;
+
+const DEFAULT_LOG_LIMIT: i64 = 100;
+
+/// A failed fetch with a coarse cause: `timeout`, `dns`, `tls`, `connect`,
+/// `redirect`, `body` or `request`. Pages that load but are dead get
+/// `http_4xx`, `http_5xx`, `soft_404` or `parked` instead (see `page_class`).
+#[derive(Debug)]
+pub(crate) struct FetchError {
+    pub message: String,
+    pub class: &'static str,
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(error: reqwest::Error) -> Self {
+        // reqwest only flags connect errors; the cause is in the source chain
+        let mut causes = String::new();
+        let mut source = std::error::Error::source(&error);
+        while let Some(cause) = source {
+            causes.push_str(&cause.to_string().to_lowercase());
+            causes.push('\n');
+            source = cause.source();
+        }
+
+        let class = if error.is_timeout() || causes.contains("timed out") {
+            "timeout"
+        } else if error.is_redirect() {
+            "redirect"
+        } else if causes.contains("dns") || causes.contains("lookup") || causes.contains("name or service not known")
+            || causes.contains("no such host")
+        {
+            "dns"
+        } else if causes.contains("certificate") || causes.contains("tls") || causes.contains("ssl") || causes.contains("handshake") {
+            "tls"
+        } else if error.is_connect() {
+            "connect"
+        } else if error.is_body() || error.is_decode() {
+            "body"
+        } else {
+            "request"
+        };
+        FetchError { message: error.to_string(), class }
+    }
+}
+
+impl From<String> for FetchError {
+    fn from(message: String) -> Self {
+        FetchError { message, class: "request" }
+    }
+}
+
+/// Error class for an HTTP status that is not a success.
+pub(crate) fn status_class(status: u16) -> Option<&'static str> {
+    match status {
+        200..=299 => None,
+        300..=399 => Some("redirect"),
+        400..=499 => Some("http_4xx"),
+        _ => Some("http_5xx"),
+    }
+}
+
+/// Error class of a fetch that got an answer: a failing status, or why the
+/// page is dead despite loading (see `liveness::dead_page_reason`).
+pub(crate) fn page_class(page: &PageMetadata) -> Option<&'static str> {
+    page.dead_reason.or_else(|| page.status.and_then(status_class))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlLogEntry {
+    pub crawled_at: String,
+    pub status_code: Option<i64>,
+    pub final_url: Option<String>,
+    pub latency_ms: i64,
+    pub bytes: i64,
+    pub error_class: Option<String>,
+    pub error: Option<String>,
+    /// URLs redirected from, the requested one first
+    pub redirect_chain: Vec<String>,
+}
+
+/// Records one fetch of `node_id` and stores its status on the node.
+pub(crate) fn log_fetch(
+    conn: &Connection,
+    node_id: i64,
+    outcome: &Result<PageMetadata, FetchError>,
+    latency_ms: u128,
+) -> Result<(), String> {
+    let (status, final_url, bytes, class, error, redirect_chain) = match outcome {
+        Ok(page) => (
+            page.status,
+            page.final_url.as_deref(),
+            page.bytes as i64,
+            page_class(page),
+            None,
+            (!page.redirect_chain.is_empty())
+                .then(|| serde_json::to_string(&page.redirect_chain))
+                .transpose()
+                .map_err(|e| e.to_string())?,
+        ),
+        Err(e) => (None, None, 0, Some(e.class), Some(e.message.as_str()), None),
+    };
+
+    conn.execute(
+        "INSERT INTO crawl_log (node_id, crawled_at, status_code, final_url, latency_ms, bytes, error_class, error, redirect_chain)
+         VALUES (?1, datetime('now'), ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![node_id, status, final_url, latency_ms as i64, bytes, class, error, redirect_chain],
+    ).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE nodes SET last_status = ?1, last_error_class = ?2 WHERE id = ?3",
+        params![status, class, node_id],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Fetches of a node, newest first.
+#[tauri::command]
+pub async fn get_crawl_log(app: tauri::AppHandle, node_id: i64, limit: Option<i64>) -> Result<Vec<CrawlLogEntry>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT crawled_at, status_code, final_url, latency_ms, bytes, error_class, error, redirect_chain
+         FROM crawl_log WHERE node_id = ?1 ORDER BY id DESC LIMIT ?2"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![node_id, limit.unwrap_or(DEFAULT_LOG_LIMIT)], |row| {
+        Ok(CrawlLogEntry {
+            crawled_at: row.get(0)?,
+            status_code: row.get(1)?,
+            final_url: row.get(2)?,
+            latency_ms: row.get(3)?,
+            bytes: row.get(4)?,
+            error_class: row.get(5)?,
+            error: row.get(6)?,
+            redirect_chain: row.get::<_, Option<String>>(7)?
+                .and_then(|chain| serde_json::from_str(&chain).ok())
+                .unwrap_or_default(),
+        })
+    }).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
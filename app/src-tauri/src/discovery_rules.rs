@@ -0,0 +1,235 @@
+// ============== DISCOVERY RULES ==============
+//
+// Which links discovery may turn into new nodes. The rules live in the void,
+// so every session keeps its own, and are checked by `discover_links_from_node`
+// (page links, feed items and sitemap pages alike) and `seed_from_sitemap`.
+// Links to nodes already in the void are always joined with an edge.
+//
+// URL patterns are globs over the whole URL (`*` any run of characters, `?`
+// one, case-insensitive) unless prefixed with `re:`, which makes the rest a
+// regular expression that may match anywhere in the URL.
+
+use serde::{Deserialize, Serialize};
+use regex::{Regex, RegexBuilder};
+use rusqlite::{Connection, OptionalExtension, params};
+
+const REGEX_PREFIX: &str = "re:";
+
+const DEFAULT_MAX_URL_LENGTH: usize = 300;
+
+/// Downloads and media rather than pages.
+const DEFAULT_SKIP_EXTENSIONS: &[&str] = &[
+    "pdf", "zip", "gz", "tgz", "rar", "7z", "tar", "exe", "msi", "dmg", "pkg", "deb", "rpm", "apk", "iso",
+    "jpg", "jpeg", "png", "gif", "webp", "svg", "ico", "bmp", "mp3", "wav", "ogg", "flac", "mp4", "mov",
+    "avi", "mkv", "webm", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "csv", "json", "xml", "txt",
+    "css", "js", "woff", "woff2", "ttf",
+];
+
+/// Account pages and "share this" links.
+const DEFAULT_EXCLUDE_PATTERNS: &[&str] = &[
+    "*/login*", "*/log-in*", "*/signin*", "*/sign-in*", "*/signup*", "*/sign-up*", "*/register*",
+    "*/logout*", "*/account/*", "*/oauth*", "*/auth/*", "*/cart*", "*/checkout*",
+    "*/sharer*", "*/share?*", "*/share/*", "*/intent/tweet*", "*/intent/post*", "*/sharearticle*",
+    "*/submit?url=*", "*/pin/create/*", "*?replytocom=*",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscoveryRules {
+    /// When not empty, only URLs matching one of these are added
+    pub include_patterns: Vec<String>,
+    /// URLs matching any of these are never added
+    pub exclude_patterns: Vec<String>,
+    /// When not empty, only these domains (and their subdomains) are added
+    pub allow_domains: Vec<String>,
+    /// Domains (and their subdomains) never added
+    pub deny_domains: Vec<String>,
+    pub max_url_length: Option<usize>,
+    /// Extensions of the last path segment to skip, without the dot
+    pub skip_extensions: Vec<String>,
+    /// How many links deep discovery may follow within one site from the
+    /// page it entered the site on, unlimited when not set
+    pub max_same_site_depth: Option<i64>,
+}
+
+impl Default for DiscoveryRules {
+    fn default() -> Self {
+        DiscoveryRules {
+            include_patterns: vec![],
+            exclude_patterns: DEFAULT_EXCLUDE_PATTERNS.iter().map(|p| p.to_string()).collect(),
+            allow_domains: vec![],
+            deny_domains: vec![],
+            max_url_length: Some(DEFAULT_MAX_URL_LENGTH),
+            skip_extensions: DEFAULT_SKIP_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            max_same_site_depth: None,
+        }
+    }
+}
+
+/// Rules with their patterns compiled, ready to check links against.
+pub(crate) struct RuleSet {
+    rules: DiscoveryRules,
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    match pattern.strip_prefix(REGEX_PREFIX) {
+        Some(expr) => Regex::new(expr),
+        None => {
+            let expr = regex::escape(pattern).replace(r"\*", ".*").replace(r"\?", ".");
+            RegexBuilder::new(&format!("^{}$", expr)).case_insensitive(true).build()
+        }
+    }.map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))
+}
+
+fn normalize_domain(domain: &str) -> String {
+    domain.trim().trim_start_matches("*.").trim_matches('.').to_lowercase()
+}
+
+fn matches_domain(host: &str, domains: &[String]) -> bool {
+    domains.iter().any(|d| host == d || host.strip_suffix(d.as_str()).is_some_and(|rest| rest.ends_with('.')))
+}
+
+/// Host without `www.`, so `www.x.com` and `x.com` count as one site.
+fn site_of(url: &str) -> Option<String> {
+    url::Url::parse(url).ok()?.host_str().map(|h| h.trim_start_matches("www.").to_lowercase())
+}
+
+impl DiscoveryRules {
+    /// Trims and lowercases the lists and drops empty entries.
+    fn normalized(mut self) -> Self {
+        let clean = |list: Vec<String>, f: fn(&str) -> String| -> Vec<String> {
+            let mut out: Vec<String> = vec![];
+            for item in list.iter().map(|s| f(s)).filter(|s| !s.is_empty()) {
+                if !out.contains(&item) {
+                    out.push(item);
+                }
+            }
+            out
+        };
+        self.include_patterns = clean(self.include_patterns, |p| p.trim().to_string());
+        self.exclude_patterns = clean(self.exclude_patterns, |p| p.trim().to_string());
+        self.allow_domains = clean(self.allow_domains, normalize_domain);
+        self.deny_domains = clean(self.deny_domains, normalize_domain);
+        self.skip_extensions = clean(self.skip_extensions, |e| e.trim().trim_start_matches('.').to_lowercase());
+        self
+    }
+}
+
+impl RuleSet {
+    pub(crate) fn compile(rules: DiscoveryRules) -> Result<Self, String> {
+        let rules = rules.normalized();
+        if rules.max_url_length == Some(0) {
+            return Err("max_url_length must be at least 1".to_string());
+        }
+        if rules.max_same_site_depth.is_some_and(|d| d < 0) {
+            return Err("max_same_site_depth cannot be negative".to_string());
+        }
+        let include = rules.include_patterns.iter().map(|p| compile_pattern(p)).collect::<Result<_, _>>()?;
+        let exclude = rules.exclude_patterns.iter().map(|p| compile_pattern(p)).collect::<Result<_, _>>()?;
+        Ok(RuleSet { rules, include, exclude })
+    }
+
+    /// Whether `url` may become a node.
+    pub(crate) fn allows(&self, url: &str) -> bool {
+        let rules = &self.rules;
+        if rules.max_url_length.is_some_and(|max| url.len() > max) {
+            return false;
+        }
+        let Ok(parsed) = url::Url::parse(url) else {
+            return false;
+        };
+        let host = parsed.host_str().unwrap_or_default().to_lowercase();
+        if !rules.allow_domains.is_empty() && !matches_domain(&host, &rules.allow_domains) {
+            return false;
+        }
+        if matches_domain(&host, &rules.deny_domains) {
+            return false;
+        }
+        let extension = parsed.path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(|segment| segment.rsplit_once('.'))
+            .map(|(_, ext)| ext.to_lowercase());
+        if extension.is_some_and(|ext| rules.skip_extensions.contains(&ext)) {
+            return false;
+        }
+        if !self.include.is_empty() && !self.include.iter().any(|r| r.is_match(url)) {
+            return false;
+        }
+        !self.exclude.iter().any(|r| r.is_match(url))
+    }
+
+    /// Site depth of a new node for `url` linked from `source_url`: one more
+    /// than the source's within the same site, 0 on another site. None when
+    /// that is past `max_same_site_depth`.
+    pub(crate) fn link_depth(&self, source_url: &str, source_depth: i64, url: &str) -> Option<i64> {
+        if site_of(source_url).is_none() || site_of(source_url) != site_of(url) {
+            return Some(0);
+        }
+        let depth = source_depth + 1;
+        match self.rules.max_same_site_depth {
+            Some(max) if depth > max => None,
+            _ => Some(depth),
+        }
+    }
+}
+
+/// The void's discovery rules, the defaults if none were saved.
+pub(crate) fn load_rules(conn: &Connection) -> Result<DiscoveryRules, String> {
+    let saved: Option<String> = conn.query_row("SELECT rules FROM discovery_rules WHERE id = 1", [], |row| row.get(0))
+        .optional()
+        .map_err(|e| e.to_string())?;
+    match saved {
+        Some(json) => serde_json::from_str(&json).map_err(|e| format!("Invalid discovery rules: {}", e)),
+        None => Ok(DiscoveryRules::default()),
+    }
+}
+
+pub(crate) fn load_rule_set(conn: &Connection) -> Result<RuleSet, String> {
+    RuleSet::compile(load_rules(conn)?)
+}
+
+/// Site depth stored for `node_id`; nodes added by hand or before depths
+/// were tracked count as entry points.
+pub(crate) fn site_depth(conn: &Connection, node_id: i64) -> Result<i64, String> {
+    conn.query_row("SELECT COALESCE(site_depth, 0) FROM nodes WHERE id = ?1", params![node_id], |row| row.get(0))
+        .map_err(|e| e.to_string())
+}
+
+pub(crate) fn save_rules_in(conn: &Connection, rules: DiscoveryRules) -> Result<DiscoveryRules, String> {
+    let rules = RuleSet::compile(rules)?.rules;
+    let json = serde_json::to_string(&rules).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO discovery_rules (id, rules, updated_at) VALUES (1, ?1, datetime('now'))
+         ON CONFLICT(id) DO UPDATE SET rules = excluded.rules, updated_at = excluded.updated_at",
+        params![json],
+    ).map_err(|e| e.to_string())?;
+    Ok(rules)
+}
+
+/// The active session's discovery rules.
+#[tauri::command]
+pub async fn get_discovery_rules(app: tauri::AppHandle) -> Result<DiscoveryRules, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+    load_rules(&conn)
+}
+
+/// Replaces the active session's discovery rules after checking every
+/// pattern compiles. Returns the rules as saved, with lists cleaned up.
+#[tauri::command]
+pub async fn set_discovery_rules(app: tauri::AppHandle, rules: DiscoveryRules) -> Result<DiscoveryRules, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+    save_rules_in(&conn, rules)
+}
+
+/// Puts the active session back on the default rules.
+#[tauri::command]
+pub async fn reset_discovery_rules(app: tauri::AppHandle) -> Result<DiscoveryRules, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+    conn.execute("DELETE FROM discovery_rules", []).map_err(|e| e.to_string())?;
+    Ok(DiscoveryRules::default())
+}
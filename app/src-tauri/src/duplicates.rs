@@ -0,0 +1,316 @@
+// ============== DUPLICATE NODES ==============
+//
+// Finds nodes that are the same page under different URLs and merges them.
+// Candidates are grouped three ways: by canonical URL (scheme, `www.`,
+// trailing slashes, index pages and tracking parameters ignored), by where
+// their redirects end, and by the hash of their last crawled text.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use rusqlite::{Connection, params};
+
+use crate::{VoidNode, VOID_NODE_COLUMNS, void_node_from_row};
+use crate::redirects;
+
+/// Query parameters that only track where a visitor came from.
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "_ga", "igshid", "yclid"];
+const INDEX_PAGES: &[&str] = &["index.html", "index.htm", "index.php", "default.aspx", "default.htm"];
+/// Pages with less text than this (error pages, app shells) share hashes too
+/// easily to count as duplicates.
+const MIN_CONTENT_CHARS: i64 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    /// `canonical_url`, `redirect` or `content`
+    pub reason: String,
+    /// The canonical URL, redirect target or content hash shared by the group
+    pub key: String,
+    pub nodes: Vec<VoidNode>,
+    /// The node `merge_nodes` should keep: not an alias, alive, https, best linked
+    pub suggested_keep_id: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeMergeResult {
+    /// The kept node with the merged metadata
+    pub node: VoidNode,
+    pub removed_ids: Vec<i64>,
+    pub edge_count: i32,
+}
+
+/// A URL reduced to what identifies the page: host without `www.`, path
+/// without index page or trailing slash, and sorted query parameters minus
+/// tracking ones. The scheme, default ports and fragment are dropped.
+pub(crate) fn canonical_url(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return None;
+    }
+    let host = parsed.host_str()?.trim_start_matches("www.");
+
+    let mut path = parsed.path().to_string();
+    if let Some(page) = INDEX_PAGES.iter().find(|p| path.to_lowercase().ends_with(&format!("/{}", p))) {
+        path.truncate(path.len() - page.len());
+    }
+    let path = path.trim_end_matches('/');
+
+    let mut query: Vec<(String, String)> = parsed.query_pairs()
+        .filter(|(k, _)| !k.starts_with("utm_") && !TRACKING_PARAMS.contains(&k.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    query.sort();
+
+    let mut canonical = match parsed.port() {
+        Some(port) => format!("{}:{}{}", host, port, path),
+        None => format!("{}{}", host, path),
+    };
+    if !query.is_empty() {
+        let pairs: Vec<String> = query.iter().map(|(k, v)| if v.is_empty() { k.clone() } else { format!("{}={}", k, v) }).collect();
+        canonical.push('?');
+        canonical.push_str(&pairs.join("&"));
+    }
+    Some(canonical)
+}
+
+fn host_of(url: &str) -> Option<String> {
+    url::Url::parse(url).ok().and_then(|u| u.host_str().map(|h| h.to_string()))
+}
+
+/// Discovery titles new nodes after their host until they are crawled.
+fn has_real_title(node: &VoidNode) -> bool {
+    let title = node.title.trim();
+    !title.is_empty() && title != "Unknown" && Some(title) != host_of(&node.url).as_deref()
+}
+
+fn load_nodes(conn: &Connection) -> Result<Vec<VoidNode>, String> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM nodes ORDER BY id", VOID_NODE_COLUMNS))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], void_node_from_row).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+fn load_degrees(conn: &Connection) -> Result<HashMap<i64, i64>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT id, COUNT(*) FROM (
+             SELECT source_id AS id FROM edges UNION ALL SELECT target_id FROM edges
+         ) GROUP BY id"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+/// Where each node's redirects end: its canonical node's URL if it is an
+/// alias, else the final URL of its last fetch when that differs from its own.
+fn redirect_targets(conn: &Connection, nodes: &[VoidNode]) -> Result<Vec<(i64, String)>, String> {
+    let urls: HashMap<i64, &str> = nodes.iter().map(|n| (n.id, n.url.as_str())).collect();
+    let final_urls: HashMap<i64, String> = {
+        let mut stmt = conn.prepare(
+            "SELECT node_id, final_url FROM crawl_log c
+             WHERE id = (SELECT MAX(id) FROM crawl_log WHERE node_id = c.node_id) AND final_url IS NOT NULL"
+        ).map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?;
+        rows.flatten().collect()
+    };
+
+    Ok(nodes.iter().filter_map(|node| {
+        let target = match node.canonical_node_id.and_then(|id| urls.get(&id)) {
+            Some(url) => url.to_string(),
+            None => crate::clean_link(final_urls.get(&node.id)?)?,
+        };
+        (target != node.url.trim_end_matches('/')).then_some((node.id, target))
+    }).collect())
+}
+
+fn content_hashes(conn: &Connection) -> Result<Vec<(i64, String)>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT node_id, content_hash FROM crawl_history h
+         WHERE id = (SELECT MAX(id) FROM crawl_history WHERE node_id = h.node_id) AND text_length >= ?1"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![MIN_CONTENT_CHARS], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+pub(crate) fn find_duplicates_in(conn: &Connection) -> Result<Vec<DuplicateGroup>, String> {
+    let nodes = load_nodes(conn)?;
+    let degrees = load_degrees(conn)?;
+    let by_id: HashMap<i64, &VoidNode> = nodes.iter().map(|n| (n.id, n)).collect();
+
+    let mut candidates: Vec<(&str, BTreeMap<String, Vec<i64>>)> = vec![];
+
+    let mut by_canonical: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    for node in &nodes {
+        if let Some(key) = canonical_url(&node.url) {
+            by_canonical.entry(key).or_default().push(node.id);
+        }
+    }
+    candidates.push(("canonical_url", by_canonical));
+
+    // A redirect target belongs with the nodes redirecting to it
+    let mut by_target: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    for (id, target) in redirect_targets(conn, &nodes)? {
+        by_target.entry(target).or_default().push(id);
+    }
+    for node in &nodes {
+        if let Some(ids) = by_target.get_mut(node.url.trim_end_matches('/')) {
+            ids.push(node.id);
+        }
+    }
+    candidates.push(("redirect", by_target));
+
+    let mut by_hash: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+    for (id, hash) in content_hashes(conn)? {
+        by_hash.entry(hash).or_default().push(id);
+    }
+    candidates.push(("content", by_hash));
+
+    // The same nodes often match for several reasons; report them once
+    let mut seen: HashSet<Vec<i64>> = HashSet::new();
+    let mut groups = vec![];
+    for (reason, keyed) in candidates {
+        for (key, mut ids) in keyed {
+            ids.sort_unstable();
+            ids.dedup();
+            if ids.len() < 2 || !seen.insert(ids.clone()) {
+                continue;
+            }
+            let members: Vec<VoidNode> = ids.iter().filter_map(|id| by_id.get(id).map(|n| (*n).clone())).collect();
+            let Some(keep) = members.iter().max_by_key(|n| keep_rank(n, degrees.get(&n.id).copied().unwrap_or(0))) else {
+                continue;
+            };
+            groups.push(DuplicateGroup {
+                reason: reason.to_string(),
+                key,
+                suggested_keep_id: keep.id,
+                nodes: members,
+            });
+        }
+    }
+    groups.sort_by_key(|g| Reverse(g.nodes.len()));
+    Ok(groups)
+}
+
+fn keep_rank(node: &VoidNode, degree: i64) -> impl Ord {
+    (
+        node.canonical_node_id.is_none(),
+        node.is_alive,
+        node.url.starts_with("https://"),
+        degree,
+        node.visit_count,
+        Reverse(node.id),
+    )
+}
+
+/// `keep` with gaps filled from `others`: a real title, favicon, screenshot
+/// and description if it has none, the latest timestamps, summed visits and
+/// the earliest creation date. It stays pinned or alive if any of them is.
+fn merged_metadata(keep: &VoidNode, others: &[VoidNode]) -> VoidNode {
+    let mut merged = keep.clone();
+    if !has_real_title(&merged) {
+        if let Some(other) = others.iter().find(|n| has_real_title(n)) {
+            merged.title = other.title.clone();
+        }
+    }
+    for other in others {
+        merged.favicon = merged.favicon.or_else(|| other.favicon.clone());
+        merged.screenshot = merged.screenshot.or_else(|| other.screenshot.clone());
+        merged.description = merged.description.or_else(|| other.description.clone());
+        merged.last_crawled = merged.last_crawled.max(other.last_crawled.clone());
+        merged.last_visited = merged.last_visited.max(other.last_visited.clone());
+        merged.last_changed = merged.last_changed.max(other.last_changed.clone());
+        if !other.created_at.is_empty() && (merged.created_at.is_empty() || other.created_at < merged.created_at) {
+            merged.created_at = other.created_at.clone();
+        }
+        merged.cluster_id = merged.cluster_id.or(other.cluster_id);
+        merged.visit_count += other.visit_count;
+        merged.pinned |= other.pinned;
+        merged.is_alive |= other.is_alive;
+    }
+    merged
+}
+
+pub(crate) fn merge_nodes_in(conn: &mut Connection, keep_id: i64, remove_ids: &[i64]) -> Result<NodeMergeResult, String> {
+    let mut remove_ids = remove_ids.to_vec();
+    remove_ids.sort_unstable();
+    remove_ids.dedup();
+    if remove_ids.contains(&keep_id) {
+        return Err("The kept node cannot also be removed".to_string());
+    }
+    if remove_ids.is_empty() {
+        return Err("No nodes to merge".to_string());
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let (keep, others) = {
+        let mut stmt = tx.prepare(&format!("SELECT {} FROM nodes WHERE id = ?1", VOID_NODE_COLUMNS))
+            .map_err(|e| e.to_string())?;
+        let mut fetch = |id: i64| stmt.query_row(params![id], void_node_from_row)
+            .map_err(|_| format!("Node {} not found", id));
+        let keep = fetch(keep_id)?;
+        let others = remove_ids.iter().map(|&id| fetch(id)).collect::<Result<Vec<_>, _>>()?;
+        (keep, others)
+    };
+
+    let merged = merged_metadata(&keep, &others);
+    tx.execute(
+        "UPDATE nodes SET title = ?1, favicon = ?2, screenshot = ?3, description = ?4, last_crawled = ?5,
+            last_visited = ?6, last_changed = ?7, created_at = ?8, cluster_id = ?9, visit_count = ?10,
+            pinned = ?11, is_alive = ?12
+         WHERE id = ?13",
+        params![
+            merged.title,
+            merged.favicon,
+            merged.screenshot,
+            merged.description,
+            merged.last_crawled,
+            merged.last_visited,
+            merged.last_changed,
+            merged.created_at,
+            merged.cluster_id,
+            merged.visit_count,
+            if merged.pinned { 1 } else { 0 },
+            if merged.is_alive { 1 } else { 0 },
+            keep_id,
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    for &id in &remove_ids {
+        redirects::merge_node_into(&tx, id, keep_id)?;
+    }
+
+    let node = tx.query_row(
+        &format!("SELECT {} FROM nodes WHERE id = ?1", VOID_NODE_COLUMNS),
+        params![keep_id],
+        void_node_from_row,
+    ).map_err(|e| e.to_string())?;
+    let edge_count: i32 = tx.query_row(
+        "SELECT COUNT(*) FROM edges WHERE source_id = ?1 OR target_id = ?1",
+        params![keep_id],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(NodeMergeResult { node, removed_ids: remove_ids, edge_count })
+}
+
+/// Groups of nodes that look like the same page, largest first. A node can
+/// be in several groups; groups with exactly the same nodes are listed once.
+#[tauri::command]
+pub async fn find_duplicate_nodes(app: tauri::AppHandle) -> Result<Vec<DuplicateGroup>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+
+    std::thread::spawn(move || {
+        let conn = crate::open_void_db(&db_path)?;
+        find_duplicates_in(&conn)
+    }).join().map_err(|_| "Thread panic")?
+}
+
+/// Merges `remove_ids` into `keep_id` in one transaction: their edges are
+/// re-pointed, missing metadata is filled in from them and they are deleted.
+#[tauri::command]
+pub async fn merge_nodes(app: tauri::AppHandle, keep_id: i64, remove_ids: Vec<i64>) -> Result<NodeMergeResult, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let mut conn = crate::open_void_db(&db_path)?;
+    merge_nodes_in(&mut conn, keep_id, &remove_ids)
+}
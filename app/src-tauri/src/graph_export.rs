@@ -0,0 +1,377 @@
+// ============== GRAPH EXPORT (GEXF / GraphML / DOT / CSV) ==============
+//
+// Serializes the active void straight from SQLite to disk for tools like Gephi
+// and networkx. Rows are streamed through a buffered writer, so even very large
+// voids never get loaded into memory or round-tripped through the webview.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use rusqlite::Connection;
+use tauri::Manager;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphExportResult {
+    pub format: String,
+    /// One file for GEXF/GraphML/DOT, two (`-nodes.csv`, `-edges.csv`) for CSV
+    pub files: Vec<String>,
+    pub node_count: i64,
+    pub edge_count: i64,
+}
+
+/// A node row as exported, with the domain derived from the URL.
+struct ExportNode {
+    id: i64,
+    url: String,
+    title: String,
+    domain: String,
+    /// None when a coordinate is missing or not a finite number
+    position: Option<[f64; 3]>,
+    is_alive: bool,
+    last_crawled: Option<String>,
+    created_at: Option<String>,
+}
+
+fn read_position(row: &rusqlite::Row) -> rusqlite::Result<Option<[f64; 3]>> {
+    let mut position = [0.0; 3];
+    for (i, coordinate) in position.iter_mut().enumerate() {
+        match row.get::<_, Option<f64>>(3 + i)? {
+            Some(v) if v.is_finite() => *coordinate = v,
+            _ => return Ok(None),
+        }
+    }
+    Ok(Some(position))
+}
+
+fn for_each_node(conn: &Connection, mut f: impl FnMut(ExportNode) -> std::io::Result<()>) -> Result<i64, String> {
+    let mut stmt = conn.prepare(
+        "SELECT id, url, title, position_x, position_y, position_z, is_alive, last_crawled, created_at
+         FROM nodes ORDER BY id"
+    ).map_err(|e| e.to_string())?;
+    let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+    let mut count = 0;
+
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let url: String = row.get(1).map_err(|e| e.to_string())?;
+        let domain = url::Url::parse(&url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .unwrap_or_default();
+        let node = ExportNode {
+            id: row.get(0).map_err(|e| e.to_string())?,
+            title: row.get::<_, Option<String>>(2).map_err(|e| e.to_string())?.unwrap_or_default(),
+            url,
+            domain,
+            position: read_position(row).map_err(|e| e.to_string())?,
+            is_alive: row.get::<_, i32>(6).map_err(|e| e.to_string())? == 1,
+            last_crawled: row.get(7).map_err(|e| e.to_string())?,
+            created_at: row.get(8).map_err(|e| e.to_string())?,
+        };
+        f(node).map_err(|e| e.to_string())?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+struct ExportEdge {
+    id: i64,
+    source: i64,
+    target: i64,
+    edge_type: String,
+}
+
+fn for_each_edge(conn: &Connection, mut f: impl FnMut(ExportEdge) -> std::io::Result<()>) -> Result<i64, String> {
+    // Skip dangling edges so strict readers (networkx, Gephi) don't reject the file
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.source_id, e.target_id, e.edge_type FROM edges e
+         JOIN nodes s ON s.id = e.source_id
+         JOIN nodes t ON t.id = e.target_id
+         ORDER BY e.id"
+    ).map_err(|e| e.to_string())?;
+    let mut rows = stmt.query([]).map_err(|e| e.to_string())?;
+    let mut count = 0;
+
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let edge = ExportEdge {
+            id: row.get(0).map_err(|e| e.to_string())?,
+            source: row.get(1).map_err(|e| e.to_string())?,
+            target: row.get(2).map_err(|e| e.to_string())?,
+            edge_type: row.get(3).map_err(|e| e.to_string())?,
+        };
+        f(edge).map_err(|e| e.to_string())?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Control characters other than tab/newline are invalid in XML 1.0
+            c if (c as u32) < 0x20 && c != '\t' && c != '\n' && c != '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace(['\n', '\r'], " ")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn create_writer(path: &Path) -> Result<BufWriter<fs::File>, String> {
+    let file = fs::File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    Ok(BufWriter::with_capacity(1 << 16, file))
+}
+
+fn write_gexf(conn: &Connection, path: &Path) -> Result<(i64, i64), String> {
+    let mut w = create_writer(path)?;
+    let io = |e: std::io::Error| e.to_string();
+
+    write!(
+        w,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<gexf xmlns="http://gexf.net/1.3" xmlns:viz="http://gexf.net/1.3/viz" version="1.3">
+  <meta lastmodifieddate="{}">
+    <creator>Void Browser {}</creator>
+  </meta>
+  <graph defaultedgetype="directed" mode="static">
+    <attributes class="node">
+      <attribute id="url" title="url" type="string"/>
+      <attribute id="domain" title="domain" type="string"/>
+      <attribute id="is_alive" title="is_alive" type="boolean"/>
+      <attribute id="last_crawled" title="last_crawled" type="string"/>
+      <attribute id="created_at" title="created_at" type="string"/>
+    </attributes>
+    <attributes class="edge">
+      <attribute id="edge_type" title="edge_type" type="string"/>
+    </attributes>
+    <nodes>
+"#,
+        chrono::Local::now().format("%Y-%m-%d"),
+        env!("CARGO_PKG_VERSION")
+    ).map_err(io)?;
+
+    let node_count = for_each_node(conn, |n| {
+        writeln!(w, r#"      <node id="{}" label="{}">"#, n.id, xml_escape(&n.title))?;
+        writeln!(w, "        <attvalues>")?;
+        writeln!(w, r#"          <attvalue for="url" value="{}"/>"#, xml_escape(&n.url))?;
+        writeln!(w, r#"          <attvalue for="domain" value="{}"/>"#, xml_escape(&n.domain))?;
+        writeln!(w, r#"          <attvalue for="is_alive" value="{}"/>"#, n.is_alive)?;
+        if let Some(last_crawled) = &n.last_crawled {
+            writeln!(w, r#"          <attvalue for="last_crawled" value="{}"/>"#, xml_escape(last_crawled))?;
+        }
+        if let Some(created_at) = &n.created_at {
+            writeln!(w, r#"          <attvalue for="created_at" value="{}"/>"#, xml_escape(created_at))?;
+        }
+        writeln!(w, "        </attvalues>")?;
+        if let Some([x, y, z]) = n.position {
+            writeln!(w, r#"        <viz:position x="{}" y="{}" z="{}"/>"#, x, y, z)?;
+        }
+        writeln!(w, "      </node>")
+    })?;
+
+    write!(w, "    </nodes>\n    <edges>\n").map_err(io)?;
+    let edge_count = for_each_edge(conn, |e| {
+        writeln!(w, r#"      <edge id="{}" source="{}" target="{}">"#, e.id, e.source, e.target)?;
+        writeln!(w, r#"        <attvalues><attvalue for="edge_type" value="{}"/></attvalues>"#, xml_escape(&e.edge_type))?;
+        writeln!(w, "      </edge>")
+    })?;
+    write!(w, "    </edges>\n  </graph>\n</gexf>\n").map_err(io)?;
+
+    w.flush().map_err(io)?;
+    Ok((node_count, edge_count))
+}
+
+fn write_graphml(conn: &Connection, path: &Path) -> Result<(i64, i64), String> {
+    let mut w = create_writer(path)?;
+    let io = |e: std::io::Error| e.to_string();
+
+    write!(
+        w,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns"
+         xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"
+         xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">
+  <key id="label" for="node" attr.name="label" attr.type="string"/>
+  <key id="url" for="node" attr.name="url" attr.type="string"/>
+  <key id="domain" for="node" attr.name="domain" attr.type="string"/>
+  <key id="x" for="node" attr.name="x" attr.type="double"/>
+  <key id="y" for="node" attr.name="y" attr.type="double"/>
+  <key id="z" for="node" attr.name="z" attr.type="double"/>
+  <key id="is_alive" for="node" attr.name="is_alive" attr.type="boolean"/>
+  <key id="last_crawled" for="node" attr.name="last_crawled" attr.type="string"/>
+  <key id="created_at" for="node" attr.name="created_at" attr.type="string"/>
+  <key id="edge_type" for="edge" attr.name="edge_type" attr.type="string"/>
+  <graph id="void" edgedefault="directed">
+"#
+    ).map_err(io)?;
+
+    let node_count = for_each_node(conn, |n| {
+        writeln!(w, r#"    <node id="n{}">"#, n.id)?;
+        writeln!(w, r#"      <data key="label">{}</data>"#, xml_escape(&n.title))?;
+        writeln!(w, r#"      <data key="url">{}</data>"#, xml_escape(&n.url))?;
+        writeln!(w, r#"      <data key="domain">{}</data>"#, xml_escape(&n.domain))?;
+        if let Some([x, y, z]) = n.position {
+            writeln!(w, r#"      <data key="x">{}</data>"#, x)?;
+            writeln!(w, r#"      <data key="y">{}</data>"#, y)?;
+            writeln!(w, r#"      <data key="z">{}</data>"#, z)?;
+        }
+        writeln!(w, r#"      <data key="is_alive">{}</data>"#, n.is_alive)?;
+        if let Some(last_crawled) = &n.last_crawled {
+            writeln!(w, r#"      <data key="last_crawled">{}</data>"#, xml_escape(last_crawled))?;
+        }
+        if let Some(created_at) = &n.created_at {
+            writeln!(w, r#"      <data key="created_at">{}</data>"#, xml_escape(created_at))?;
+        }
+        writeln!(w, "    </node>")
+    })?;
+
+    let edge_count = for_each_edge(conn, |e| {
+        writeln!(w, r#"    <edge id="e{}" source="n{}" target="n{}">"#, e.id, e.source, e.target)?;
+        writeln!(w, r#"      <data key="edge_type">{}</data>"#, xml_escape(&e.edge_type))?;
+        writeln!(w, "    </edge>")
+    })?;
+    write!(w, "  </graph>\n</graphml>\n").map_err(io)?;
+
+    w.flush().map_err(io)?;
+    Ok((node_count, edge_count))
+}
+
+fn write_dot(conn: &Connection, path: &Path) -> Result<(i64, i64), String> {
+    let mut w = create_writer(path)?;
+    let io = |e: std::io::Error| e.to_string();
+
+    writeln!(w, "digraph void {{").map_err(io)?;
+    let node_count = for_each_node(conn, |n| {
+        let pos = n.position
+            .map(|[x, y, z]| format!(", pos=\"{},{},{}\"", x, y, z))
+            .unwrap_or_default();
+        writeln!(
+            w,
+            "  n{} [label=\"{}\", URL=\"{}\", domain=\"{}\", alive={}{}];",
+            n.id,
+            dot_escape(&n.title),
+            dot_escape(&n.url),
+            dot_escape(&n.domain),
+            n.is_alive,
+            pos
+        )
+    })?;
+    let edge_count = for_each_edge(conn, |e| {
+        writeln!(w, "  n{} -> n{} [type=\"{}\"];", e.source, e.target, dot_escape(&e.edge_type))
+    })?;
+    writeln!(w, "}}").map_err(io)?;
+
+    w.flush().map_err(io)?;
+    Ok((node_count, edge_count))
+}
+
+fn write_csv(conn: &Connection, nodes_path: &Path, edges_path: &Path) -> Result<(i64, i64), String> {
+    let io = |e: std::io::Error| e.to_string();
+
+    let mut w = create_writer(nodes_path)?;
+    writeln!(w, "id,label,url,domain,x,y,z,is_alive,last_crawled,created_at").map_err(io)?;
+    let node_count = for_each_node(conn, |n| {
+        // Unplaced nodes get empty coordinates rather than NaN
+        let [x, y, z] = n.position.map(|p| p.map(|v| v.to_string())).unwrap_or_default();
+        writeln!(
+            w,
+            "{},{},{},{},{},{},{},{},{},{}",
+            n.id,
+            csv_field(&n.title),
+            csv_field(&n.url),
+            csv_field(&n.domain),
+            x,
+            y,
+            z,
+            if n.is_alive { 1 } else { 0 },
+            csv_field(n.last_crawled.as_deref().unwrap_or("")),
+            csv_field(n.created_at.as_deref().unwrap_or(""))
+        )
+    })?;
+    w.flush().map_err(io)?;
+
+    let mut w = create_writer(edges_path)?;
+    writeln!(w, "id,source,target,edge_type").map_err(io)?;
+    let edge_count = for_each_edge(conn, |e| {
+        writeln!(w, "{},{},{},{}", e.id, e.source, e.target, csv_field(&e.edge_type))
+    })?;
+    w.flush().map_err(io)?;
+
+    Ok((node_count, edge_count))
+}
+
+/// Writes the active void's graph to disk in `format` (`gexf`, `graphml`, `dot` or `csv`).
+/// Without `path`, the file goes into the app's `exports` folder named after the current session.
+/// For CSV, `path` is treated as a prefix and `-nodes.csv`/`-edges.csv` are appended.
+#[tauri::command]
+pub async fn export_graph(
+    app: tauri::AppHandle,
+    format: String,
+    path: Option<String>,
+) -> Result<GraphExportResult, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    if !db_path.exists() {
+        return Err("No active void to export.".to_string());
+    }
+
+    let format = format.to_lowercase();
+    let extension = match format.as_str() {
+        "gexf" | "graphml" | "dot" | "csv" => format.clone(),
+        other => return Err(format!("Unknown export format '{}'. Use gexf, graphml, dot or csv.", other)),
+    };
+
+    let base_path = match path {
+        Some(p) => PathBuf::from(p),
+        None => {
+            let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
+            let exports_dir = app_data.join("exports");
+            fs::create_dir_all(&exports_dir).map_err(|e| e.to_string())?;
+            let session = crate::get_current_session(app.clone()).await?;
+            let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+            exports_dir.join(format!("{}-{}.{}", session, timestamp, extension))
+        }
+    };
+
+    std::thread::spawn(move || {
+        let conn = crate::open_void_db(&db_path)?;
+
+        let (files, (node_count, edge_count)) = match format.as_str() {
+            "gexf" => (vec![base_path.clone()], write_gexf(&conn, &base_path)?),
+            "graphml" => (vec![base_path.clone()], write_graphml(&conn, &base_path)?),
+            "dot" => (vec![base_path.clone()], write_dot(&conn, &base_path)?),
+            _ => {
+                let stem = base_path.with_extension("");
+                let nodes_path = PathBuf::from(format!("{}-nodes.csv", stem.to_string_lossy()));
+                let edges_path = PathBuf::from(format!("{}-edges.csv", stem.to_string_lossy()));
+                let counts = write_csv(&conn, &nodes_path, &edges_path)?;
+                (vec![nodes_path, edges_path], counts)
+            }
+        };
+
+        Ok(GraphExportResult {
+            format,
+            files: files.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+            node_count,
+            edge_count,
+        })
+    }).join().map_err(|_| "Thread panic")?
+}
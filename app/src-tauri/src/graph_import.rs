@@ -0,0 +1,629 @@
+// ============== GRAPH IMPORT (GEXF / GraphML / crawler JSON) ==============
+//
+// Every importer parses into an `ImportedGraph`, which is then merged into the
+// active void the same way `import_crawler_db` does it: existing URLs are
+// reused, new nodes keep their file positions when present, and everything
+// else is placed next to an already-positioned neighbor.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+use base64::{Engine as _, engine::general_purpose};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use rand::Rng;
+use rusqlite::{Connection, params};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphImportStats {
+    pub format: String,
+    pub nodes_imported: i32,
+    pub edges_imported: i32,
+    pub nodes_skipped: i32,
+    /// Existing nodes whose visit count or last visit was refreshed
+    #[serde(default)]
+    pub nodes_updated: i32,
+    pub edges_skipped: i32,
+    /// New nodes that kept the position stored in the file
+    pub positions_preserved: i32,
+    /// New nodes that had no position and were placed by us
+    pub positions_generated: i32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ImportedNode {
+    /// The node's id in the source file; only used to resolve edges
+    pub key: String,
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub favicon: Option<String>,
+    pub screenshot: Option<String>,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub z: Option<f64>,
+    pub is_alive: Option<bool>,
+    pub last_crawled: Option<String>,
+    pub created_at: Option<String>,
+    pub visit_count: Option<i64>,
+    pub last_visited: Option<String>,
+}
+
+impl ImportedNode {
+    /// The URL this node imports as; node ids in GEXF/GraphML are often the URL itself.
+    fn import_url(&self) -> Option<String> {
+        let url = self.url.clone().or_else(|| looks_like_url(&self.key).then(|| self.key.clone()));
+        url.filter(|u| looks_like_url(u))
+    }
+
+    fn position(&self) -> Option<(f64, f64, f64)> {
+        match (self.x, self.y, self.z) {
+            (Some(x), Some(y), z) => Some((x, y, z.unwrap_or(0.0))),
+            _ => None,
+        }
+    }
+
+    /// Maps a source attribute onto the matching `VoidNode` field. Names are
+    /// compared case-insensitively with `_`, `-` and spaces ignored, so
+    /// `positionX`, `position_x` and `x` all land in the same place.
+    fn set_attribute(&mut self, name: &str, value: &str) {
+        let value = value.trim();
+        if value.is_empty() {
+            return;
+        }
+        let key: String = name.chars()
+            .filter(|c| !matches!(c, '_' | '-' | ' '))
+            .flat_map(|c| c.to_lowercase())
+            .collect();
+
+        match key.as_str() {
+            "url" | "href" | "link" | "uri" => self.url = Some(value.to_string()),
+            "label" | "title" | "name" => self.title = Some(value.to_string()),
+            "favicon" | "icon" => self.favicon = Some(value.to_string()),
+            "screenshot" | "thumbnail" => self.screenshot = Some(value.to_string()),
+            "x" | "positionx" | "posx" => self.x = value.parse().ok(),
+            "y" | "positiony" | "posy" => self.y = value.parse().ok(),
+            "z" | "positionz" | "posz" => self.z = value.parse().ok(),
+            "isalive" | "alive" => self.is_alive = parse_bool(value),
+            "lastcrawled" | "crawledat" => self.last_crawled = Some(value.to_string()),
+            "createdat" | "created" => self.created_at = Some(value.to_string()),
+            "visitcount" | "visits" => self.visit_count = value.parse().ok(),
+            "lastvisited" | "lastvisit" => self.last_visited = Some(value.to_string()),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ImportedGraph {
+    pub nodes: Vec<ImportedNode>,
+    /// (source key, target key); a key may also be a URL, see `merge_imported_graph`
+    pub edges: Vec<(String, String)>,
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" => Some(true),
+        "0" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+fn looks_like_url(value: &str) -> bool {
+    value.starts_with("http://") || value.starts_with("https://")
+}
+
+// ----- XML helpers -----
+
+pub(crate) fn xml_attributes(e: &BytesStart) -> HashMap<String, String> {
+    e.attributes()
+        .filter_map(|a| a.ok())
+        .filter_map(|a| {
+            let key = String::from_utf8_lossy(a.key.local_name().as_ref()).to_string();
+            a.unescape_value().ok().map(|v| (key, v.to_string()))
+        })
+        .collect()
+}
+
+pub(crate) fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).to_string()
+}
+
+/// Accumulates element text across Text/CData/entity events and unescapes it at the end.
+#[derive(Default)]
+pub(crate) struct XmlText(String);
+
+impl XmlText {
+    pub(crate) fn push_event(&mut self, event: &Event) {
+        match event {
+            Event::Text(t) => {
+                if let Ok(raw) = t.decode() {
+                    self.0.push_str(&raw);
+                }
+            }
+            Event::CData(c) => {
+                if let Ok(raw) = c.decode() {
+                    self.0.push_str(&quick_xml::escape::escape(raw.as_ref()));
+                }
+            }
+            Event::GeneralRef(r) => {
+                if let Ok(name) = r.decode() {
+                    self.0.push('&');
+                    self.0.push_str(&name);
+                    self.0.push(';');
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub(crate) fn take(&mut self) -> String {
+        let raw = std::mem::take(&mut self.0);
+        quick_xml::escape::unescape(&raw)
+            .map(|s| s.to_string())
+            .unwrap_or(raw)
+            .trim()
+            .to_string()
+    }
+}
+
+// ----- GEXF -----
+
+pub(crate) fn parse_gexf(xml: &str) -> Result<ImportedGraph, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut graph = ImportedGraph::default();
+
+    // attribute id -> title, so `<attvalue for="0">` can be mapped by name
+    let mut attribute_titles: HashMap<String, String> = HashMap::new();
+    let mut current: Option<ImportedNode> = None;
+
+    loop {
+        let event = reader.read_event().map_err(|e| format!("Invalid GEXF: {}", e))?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let attrs = xml_attributes(e);
+                match local_name(e).as_str() {
+                    "attribute" => {
+                        if let Some(id) = attrs.get("id") {
+                            let title = attrs.get("title").unwrap_or(id).clone();
+                            attribute_titles.insert(id.clone(), title);
+                        }
+                    }
+                    "node" => {
+                        let mut node = ImportedNode {
+                            key: attrs.get("id").cloned().unwrap_or_default(),
+                            ..Default::default()
+                        };
+                        if let Some(label) = attrs.get("label") {
+                            node.set_attribute("label", label);
+                        }
+                        if matches!(event, Event::Empty(_)) {
+                            graph.nodes.push(node);
+                        } else {
+                            current = Some(node);
+                        }
+                    }
+                    "attvalue" => {
+                        if let (Some(node), Some(key), Some(value)) = (current.as_mut(), attrs.get("for"), attrs.get("value")) {
+                            let name = attribute_titles.get(key).unwrap_or(key);
+                            node.set_attribute(name, value);
+                        }
+                    }
+                    "position" => {
+                        if let Some(node) = current.as_mut() {
+                            node.x = attrs.get("x").and_then(|v| v.parse().ok());
+                            node.y = attrs.get("y").and_then(|v| v.parse().ok());
+                            node.z = attrs.get("z").and_then(|v| v.parse().ok());
+                        }
+                    }
+                    "edge" => {
+                        if let (Some(source), Some(target)) = (attrs.get("source"), attrs.get("target")) {
+                            graph.edges.push((source.clone(), target.clone()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(e) if e.local_name().as_ref() == b"node" => {
+                if let Some(node) = current.take() {
+                    graph.nodes.push(node);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(graph)
+}
+
+// ----- GraphML -----
+
+pub(crate) fn parse_graphml(xml: &str) -> Result<ImportedGraph, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut graph = ImportedGraph::default();
+
+    // key id -> attr.name, only for node keys
+    let mut key_names: HashMap<String, String> = HashMap::new();
+    let mut current: Option<ImportedNode> = None;
+    let mut current_key: Option<String> = None;
+    let mut text = XmlText::default();
+
+    loop {
+        let event = reader.read_event().map_err(|e| format!("Invalid GraphML: {}", e))?;
+        match &event {
+            Event::Start(e) | Event::Empty(e) => {
+                let attrs = xml_attributes(e);
+                match local_name(e).as_str() {
+                    "key" => {
+                        let for_node = attrs.get("for").is_none_or(|f| f == "node" || f == "all");
+                        if let (true, Some(id)) = (for_node, attrs.get("id")) {
+                            let name = attrs.get("attr.name").unwrap_or(id).clone();
+                            key_names.insert(id.clone(), name);
+                        }
+                    }
+                    "node" => {
+                        let node = ImportedNode {
+                            key: attrs.get("id").cloned().unwrap_or_default(),
+                            ..Default::default()
+                        };
+                        if matches!(event, Event::Empty(_)) {
+                            graph.nodes.push(node);
+                        } else {
+                            current = Some(node);
+                        }
+                    }
+                    "data" if current.is_some() && matches!(event, Event::Start(_)) => {
+                        current_key = attrs.get("key").cloned();
+                        text = XmlText::default();
+                    }
+                    "edge" => {
+                        if let (Some(source), Some(target)) = (attrs.get("source"), attrs.get("target")) {
+                            graph.edges.push((source.clone(), target.clone()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Text(_) | Event::CData(_) | Event::GeneralRef(_) if current_key.is_some() => {
+                text.push_event(&event);
+            }
+            Event::End(e) => match e.local_name().as_ref() {
+                b"data" => {
+                    if let (Some(node), Some(key)) = (current.as_mut(), current_key.take()) {
+                        let name = key_names.get(&key).unwrap_or(&key).clone();
+                        node.set_attribute(&name, &text.take());
+                    }
+                }
+                b"node" => {
+                    if let Some(node) = current.take() {
+                        graph.nodes.push(node);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(graph)
+}
+
+// ----- Crawler JSON export -----
+
+fn json_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Crawler thumbnails are either a base64/data-URL string or a serialized
+/// Node `Buffer` (`{"type": "Buffer", "data": [...]}`).
+fn json_thumbnail(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) if s.starts_with("data:") => Some(s.clone()),
+        Value::String(s) if !s.is_empty() => Some(format!("data:image/png;base64,{}", s)),
+        Value::Object(obj) => {
+            let bytes: Vec<u8> = obj.get("data")?
+                .as_array()?
+                .iter()
+                .filter_map(|b| b.as_u64().map(|b| b as u8))
+                .collect();
+            (!bytes.is_empty()).then(|| format!("data:image/png;base64,{}", general_purpose::STANDARD.encode(&bytes)))
+        }
+        _ => None,
+    }
+}
+
+/// Parses the crawler's `export` output (`{ version, nodes, edges }`). Also accepts
+/// node-link JSON as written by networkx (`links` with `source`/`target`).
+pub(crate) fn parse_graph_json(json: &str) -> Result<ImportedGraph, String> {
+    let root: Value = serde_json::from_str(json).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let mut graph = ImportedGraph::default();
+
+    let nodes = root.get("nodes")
+        .and_then(|n| n.as_array())
+        .ok_or("JSON file has no \"nodes\" array")?;
+
+    for value in nodes {
+        let Some(obj) = value.as_object() else { continue };
+        let mut node = ImportedNode {
+            key: obj.get("id").and_then(json_string).unwrap_or_default(),
+            ..Default::default()
+        };
+
+        for (name, field) in obj {
+            match name.as_str() {
+                "id" => {}
+                "thumbnail" | "screenshot" => node.screenshot = json_thumbnail(field),
+                _ => match field {
+                    Value::Bool(b) => node.set_attribute(name, if *b { "true" } else { "false" }),
+                    other => {
+                        if let Some(s) = json_string(other) {
+                            node.set_attribute(name, &s);
+                        }
+                    }
+                },
+            }
+        }
+        graph.nodes.push(node);
+    }
+
+    let edges = root.get("edges")
+        .or_else(|| root.get("links"))
+        .and_then(|e| e.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    for edge in edges {
+        let source = edge.get("source_id").or_else(|| edge.get("source")).and_then(json_string);
+        // Crawler edges to pages outside the crawl have no target_id, only a target_url
+        let target = edge.get("target_id")
+            .or_else(|| edge.get("target"))
+            .and_then(json_string)
+            .or_else(|| edge.get("target_url").and_then(json_string));
+
+        if let (Some(source), Some(target)) = (source, target) {
+            graph.edges.push((source, target));
+        }
+    }
+
+    Ok(graph)
+}
+
+// ----- Merge into the void -----
+
+fn random_position() -> (f64, f64, f64) {
+    let mut rng = rand::thread_rng();
+    (rng.gen_range(-20.0..20.0), rng.gen_range(-15.0..15.0), rng.gen_range(-20.0..20.0))
+}
+
+/// Gives every node a position: nodes already in the void sit where the void
+/// has them (`stored`), other file positions are kept, the rest are placed
+/// breadth-first next to a positioned neighbor, and isolated components are
+/// seeded at a random spot.
+fn resolve_positions(
+    graph: &ImportedGraph,
+    key_index: &HashMap<&str, usize>,
+    stored: &[Option<(f64, f64, f64)>],
+) -> Vec<((f64, f64, f64), bool)> {
+    let mut positions: Vec<Option<(f64, f64, f64)>> = graph.nodes.iter()
+        .zip(stored)
+        .map(|(n, stored)| stored.or_else(|| n.position()))
+        .collect();
+    let preserved: Vec<bool> = positions.iter().map(|p| p.is_some()).collect();
+
+    let mut neighbors: Vec<Vec<usize>> = vec![vec![]; graph.nodes.len()];
+    for (source, target) in &graph.edges {
+        if let (Some(&s), Some(&t)) = (key_index.get(source.as_str()), key_index.get(target.as_str())) {
+            neighbors[s].push(t);
+            neighbors[t].push(s);
+        }
+    }
+
+    let mut queue: VecDeque<usize> = (0..positions.len()).filter(|&i| positions[i].is_some()).collect();
+    loop {
+        while let Some(i) = queue.pop_front() {
+            let Some((x, y, z)) = positions[i] else { continue };
+            for &n in &neighbors[i] {
+                if positions[n].is_none() {
+                    positions[n] = Some(crate::generate_nearby_position(x, y, z));
+                    queue.push_back(n);
+                }
+            }
+        }
+        match positions.iter().position(|p| p.is_none()) {
+            Some(i) => {
+                positions[i] = Some(random_position());
+                queue.push_back(i);
+            }
+            None => break,
+        }
+    }
+
+    positions.into_iter()
+        .zip(preserved)
+        .map(|(p, kept)| (p.unwrap_or((0.0, 0.0, 0.0)), kept))
+        .collect()
+}
+
+pub(crate) fn merge_imported_graph(
+    conn: &mut Connection,
+    graph: &ImportedGraph,
+    format: &str,
+) -> Result<GraphImportStats, String> {
+    let mut stats = GraphImportStats {
+        format: format.to_string(),
+        nodes_imported: 0,
+        edges_imported: 0,
+        nodes_skipped: 0,
+        nodes_updated: 0,
+        edges_skipped: 0,
+        positions_preserved: 0,
+        positions_generated: 0,
+    };
+
+    let key_index: HashMap<&str, usize> = graph.nodes.iter()
+        .enumerate()
+        .map(|(i, n)| (n.key.as_str(), i))
+        .collect();
+
+    let mut existing_urls: HashMap<String, i64> = HashMap::new();
+    let mut existing_positions: HashMap<i64, (f64, f64, f64)> = HashMap::new();
+    {
+        let mut stmt = conn.prepare("SELECT id, url, position_x, position_y, position_z FROM nodes")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| {
+            let position: (Option<f64>, Option<f64>, Option<f64>) = (row.get(2)?, row.get(3)?, row.get(4)?);
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, position))
+        }).map_err(|e| e.to_string())?;
+        for (id, url, position) in rows.flatten() {
+            existing_urls.insert(url, id);
+            if let (Some(x), Some(y), Some(z)) = position {
+                existing_positions.insert(id, (x, y, z));
+            }
+        }
+    }
+
+    // New neighbors of a node the void already has are placed around it
+    let stored: Vec<Option<(f64, f64, f64)>> = graph.nodes.iter()
+        .map(|n| n.import_url()
+            .and_then(|url| existing_urls.get(&url))
+            .and_then(|id| existing_positions.get(id).copied()))
+        .collect();
+    let positions = resolve_positions(graph, &key_index, &stored);
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut id_map: HashMap<&str, i64> = HashMap::new();
+
+    for (node, &((x, y, z), preserved)) in graph.nodes.iter().zip(&positions) {
+        let Some(url) = node.import_url() else {
+            stats.nodes_skipped += 1;
+            continue;
+        };
+
+        if let Some(&existing_id) = existing_urls.get(&url) {
+            id_map.insert(node.key.as_str(), existing_id);
+            if node.visit_count.is_some() || node.last_visited.is_some() {
+                // Keep the larger value so importing the same history twice is a no-op
+                let updated = tx.execute(
+                    "UPDATE nodes SET
+                        visit_count = MAX(visit_count, COALESCE(?1, 0)),
+                        last_visited = NULLIF(MAX(COALESCE(last_visited, ''), COALESCE(?2, '')), '')
+                     WHERE id = ?3 AND (visit_count < COALESCE(?1, 0) OR COALESCE(last_visited, '') < COALESCE(?2, ''))",
+                    params![node.visit_count, node.last_visited, existing_id],
+                ).map_err(|e| e.to_string())?;
+                if updated > 0 {
+                    stats.nodes_updated += 1;
+                    continue;
+                }
+            }
+            stats.nodes_skipped += 1;
+            continue;
+        }
+
+        let title = node.title.clone().unwrap_or_else(|| {
+            url::Url::parse(&url)
+                .ok()
+                .and_then(|u| u.host_str().map(|h| h.to_string()))
+                .unwrap_or_else(|| "Untitled".to_string())
+        });
+
+        tx.execute(
+            "INSERT INTO nodes (url, title, favicon, screenshot, position_x, position_y, position_z, is_alive, last_crawled, created_at, visit_count, last_visited)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, COALESCE(?10, datetime('now')), COALESCE(?11, 0), ?12)",
+            params![
+                url,
+                title,
+                node.favicon,
+                node.screenshot,
+                x,
+                y,
+                z,
+                if node.is_alive.unwrap_or(true) { 1 } else { 0 },
+                node.last_crawled,
+                node.created_at,
+                node.visit_count,
+                node.last_visited,
+            ],
+        ).map_err(|e| e.to_string())?;
+
+        let new_id = tx.last_insert_rowid();
+        id_map.insert(node.key.as_str(), new_id);
+        existing_urls.insert(url, new_id);
+        stats.nodes_imported += 1;
+        if preserved {
+            stats.positions_preserved += 1;
+        } else {
+            stats.positions_generated += 1;
+        }
+    }
+
+    let mut seen: HashSet<(i64, i64)> = HashSet::new();
+    for (source, target) in &graph.edges {
+        // Edge endpoints are node keys, but crawler exports may point at a URL instead
+        let resolve = |key: &str| id_map.get(key).copied().or_else(|| existing_urls.get(key).copied());
+
+        match (resolve(source), resolve(target)) {
+            (Some(s), Some(t)) if s != t && seen.insert((s, t)) => {
+                let inserted = tx.execute(
+                    "INSERT OR IGNORE INTO edges (source_id, target_id) VALUES (?1, ?2)",
+                    params![s, t],
+                ).map_err(|e| e.to_string())?;
+                if inserted > 0 {
+                    stats.edges_imported += 1;
+                } else {
+                    stats.edges_skipped += 1;
+                }
+            }
+            _ => stats.edges_skipped += 1,
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(stats)
+}
+
+fn detect_format(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    match ext.as_str() {
+        "gexf" => Some("gexf"),
+        "graphml" | "xml" => Some("graphml"),
+        "json" => Some("json"),
+        _ => None,
+    }
+}
+
+/// Imports a GEXF, GraphML or crawler JSON file into the active void.
+/// `format` overrides detection from the file extension.
+#[tauri::command]
+pub async fn import_graph_file(
+    app: tauri::AppHandle,
+    path: String,
+    format: Option<String>,
+) -> Result<GraphImportStats, String> {
+    let file_path = Path::new(&path);
+    let format = match format {
+        Some(f) => f.to_lowercase(),
+        None => detect_format(file_path)
+            .ok_or("Unknown file type. Expected .gexf, .graphml or .json")?
+            .to_string(),
+    };
+
+    let contents = fs::read_to_string(file_path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let graph = match format.as_str() {
+        "gexf" => parse_gexf(&contents)?,
+        "graphml" => parse_graphml(&contents)?,
+        "json" => parse_graph_json(&contents)?,
+        other => return Err(format!("Unknown import format '{}'. Use gexf, graphml or json.", other)),
+    };
+
+    let db_path = crate::get_active_db_path(&app)?;
+    let mut conn = crate::open_void_db(&db_path)?;
+
+    merge_imported_graph(&mut conn, &graph, &format)
+}
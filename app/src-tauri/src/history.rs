@@ -0,0 +1,298 @@
+// ============== BROWSER HISTORY IMPORT ==============
+//
+// Reads a copy of Chrome's `History` or Firefox's `places.sqlite`. Every
+// visited page becomes a node carrying its visit count and last visit time,
+// and each visit's `from_visit` (the page the user navigated from) becomes an
+// edge, so the void follows the paths the user actually took.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use chrono::{NaiveDate, NaiveDateTime};
+use rusqlite::{Connection, params};
+
+use crate::graph_import::{GraphImportStats, ImportedGraph, ImportedNode};
+
+/// Microseconds between 1601-01-01 (Chrome's epoch) and 1970-01-01.
+const CHROME_EPOCH_OFFSET_MICROS: i64 = 11_644_473_600_000_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryImportStats {
+    pub browser: String,
+    /// Visits inside the date range, before domain filtering
+    pub visits_scanned: i32,
+    /// Distinct pages that passed the filters
+    pub pages_found: i32,
+    #[serde(flatten)]
+    pub merge: GraphImportStats,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Browser {
+    Chrome,
+    Firefox,
+}
+
+impl Browser {
+    fn name(self) -> &'static str {
+        match self {
+            Browser::Chrome => "chrome",
+            Browser::Firefox => "firefox",
+        }
+    }
+
+    /// Visit times are stored as microseconds since 1601 (Chrome) or 1970 (Firefox).
+    fn to_unix_micros(self, stored: i64) -> i64 {
+        match self {
+            Browser::Chrome => stored - CHROME_EPOCH_OFFSET_MICROS,
+            Browser::Firefox => stored,
+        }
+    }
+
+    fn to_stored_micros(self, micros: i64) -> i64 {
+        match self {
+            Browser::Chrome => micros + CHROME_EPOCH_OFFSET_MICROS,
+            Browser::Firefox => micros,
+        }
+    }
+
+    /// One row per visit: page URL, page title, visit time and the URL of the
+    /// visit it came from, if any.
+    fn visits_query(self) -> &'static str {
+        match self {
+            Browser::Chrome => {
+                "SELECT u.url, u.title, v.visit_time, fu.url
+                 FROM visits v
+                 JOIN urls u ON u.id = v.url
+                 LEFT JOIN visits fv ON fv.id = v.from_visit
+                 LEFT JOIN urls fu ON fu.id = fv.url
+                 WHERE v.visit_time BETWEEN ?1 AND ?2
+                 ORDER BY v.visit_time"
+            }
+            Browser::Firefox => {
+                "SELECT p.url, p.title, h.visit_date, fp.url
+                 FROM moz_historyvisits h
+                 JOIN moz_places p ON p.id = h.place_id
+                 LEFT JOIN moz_historyvisits fh ON fh.id = h.from_visit
+                 LEFT JOIN moz_places fp ON fp.id = fh.place_id
+                 WHERE h.visit_date BETWEEN ?1 AND ?2
+                 ORDER BY h.visit_date"
+            }
+        }
+    }
+}
+
+struct HistoryFilter {
+    since_micros: i64,
+    until_micros: i64,
+    domains: Vec<String>,
+    exclude_domains: Vec<String>,
+    min_visits: i64,
+    max_pages: Option<usize>,
+}
+
+impl HistoryFilter {
+    fn allows(&self, url: &str) -> bool {
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return false;
+        }
+        let Some(host) = url::Url::parse(url).ok().and_then(|u| u.host_str().map(|h| h.to_lowercase())) else {
+            return false;
+        };
+        let matches = |domain: &String| host == *domain || host.ends_with(&format!(".{}", domain));
+
+        if self.exclude_domains.iter().any(matches) {
+            return false;
+        }
+        self.domains.is_empty() || self.domains.iter().any(matches)
+    }
+}
+
+fn normalize_domains(domains: Option<Vec<String>>) -> Vec<String> {
+    domains.unwrap_or_default()
+        .into_iter()
+        .map(|d| d.trim().trim_start_matches("*.").trim_start_matches('.').to_lowercase())
+        .filter(|d| !d.is_empty())
+        .collect()
+}
+
+/// Accepts `YYYY-MM-DD`, `YYYY-MM-DD HH:MM:SS` or RFC 3339. A bare date as the
+/// upper bound covers the whole day.
+fn parse_filter_date(value: &str, end_of_day: bool) -> Result<i64, String> {
+    let value = value.trim();
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.timestamp_micros());
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(dt.and_utc().timestamp_micros());
+        }
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}'. Use YYYY-MM-DD.", value))?;
+    let time = if end_of_day { date.and_hms_micro_opt(23, 59, 59, 999_999) } else { date.and_hms_opt(0, 0, 0) };
+    Ok(time.map(|t| t.and_utc().timestamp_micros()).unwrap_or_default())
+}
+
+fn format_micros(micros: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp_micros(micros).map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+fn table_exists(conn: &Connection, name: &str) -> bool {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        params![name],
+        |_| Ok(()),
+    ).is_ok()
+}
+
+fn detect_browser(conn: &Connection) -> Option<Browser> {
+    if table_exists(conn, "moz_historyvisits") {
+        Some(Browser::Firefox)
+    } else if table_exists(conn, "visits") && table_exists(conn, "urls") {
+        Some(Browser::Chrome)
+    } else {
+        None
+    }
+}
+
+struct VisitedPage {
+    title: Option<String>,
+    visits: i64,
+    last_visit: i64,
+}
+
+/// Collapses visits into pages and navigation edges.
+fn read_history(
+    conn: &Connection,
+    browser: Browser,
+    filter: &HistoryFilter,
+) -> Result<(ImportedGraph, i32, i32), String> {
+    let mut stmt = conn.prepare(browser.visits_query())
+        .map_err(|e| format!("Not a {} history database: {}", browser.name(), e))?;
+    let rows = stmt.query_map(
+        params![browser.to_stored_micros(filter.since_micros), browser.to_stored_micros(filter.until_micros)],
+        |row| Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        )),
+    ).map_err(|e| e.to_string())?;
+
+    let mut visits_scanned = 0;
+    let mut order: Vec<String> = vec![];
+    let mut pages: HashMap<String, VisitedPage> = HashMap::new();
+    let mut edges: Vec<(String, String)> = vec![];
+    let mut seen_edges: HashSet<(String, String)> = HashSet::new();
+
+    for (url, title, visit_time, from_url) in rows.flatten() {
+        visits_scanned += 1;
+        if !filter.allows(&url) {
+            continue;
+        }
+        let visit_time = browser.to_unix_micros(visit_time);
+        let title = title.filter(|t| !t.trim().is_empty());
+
+        let page = pages.entry(url.clone()).or_insert_with(|| {
+            order.push(url.clone());
+            VisitedPage { title: None, visits: 0, last_visit: visit_time }
+        });
+        page.visits += 1;
+        page.last_visit = page.last_visit.max(visit_time);
+        if title.is_some() {
+            page.title = title;
+        }
+
+        if let Some(from_url) = from_url.filter(|f| *f != url && filter.allows(f)) {
+            if seen_edges.insert((from_url.clone(), url.clone())) {
+                edges.push((from_url, url));
+            }
+        }
+    }
+
+    order.retain(|url| pages[url].visits >= filter.min_visits);
+    if let Some(max) = filter.max_pages {
+        // Keep the most visited pages; the sort is stable so ties stay in visit order
+        order.sort_by(|a, b| pages[b].visits.cmp(&pages[a].visits));
+        order.truncate(max);
+    }
+
+    let pages_found = order.len() as i32;
+    let nodes: Vec<ImportedNode> = order.into_iter()
+        .map(|url| {
+            let page = &pages[&url];
+            ImportedNode {
+                key: url.clone(),
+                url: Some(url),
+                title: page.title.clone(),
+                visit_count: Some(page.visits),
+                last_visited: format_micros(page.last_visit),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    // Edges to pages that were filtered out can still land on nodes already in the void
+    Ok((ImportedGraph { nodes, edges }, visits_scanned, pages_found))
+}
+
+/// Imports a browser history database into the active void. The file is
+/// copied first since browsers keep it locked while running. `browser`
+/// (`chrome` or `firefox`) overrides detection from the tables present.
+/// `since`/`until` take `YYYY-MM-DD` dates; `domains` limits the import to
+/// those sites (subdomains included) and `exclude_domains` drops sites.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn import_browser_history(
+    app: tauri::AppHandle,
+    path: String,
+    browser: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+    domains: Option<Vec<String>>,
+    exclude_domains: Option<Vec<String>>,
+    min_visits: Option<i64>,
+    max_pages: Option<usize>,
+) -> Result<HistoryImportStats, String> {
+    let file_path = Path::new(&path);
+    if !file_path.exists() {
+        return Err("History file not found".to_string());
+    }
+
+    let filter = HistoryFilter {
+        since_micros: since.as_deref().map(|d| parse_filter_date(d, false)).transpose()?.unwrap_or(0),
+        until_micros: until.as_deref().map(|d| parse_filter_date(d, true)).transpose()?.unwrap_or(i64::MAX / 2),
+        domains: normalize_domains(domains),
+        exclude_domains: normalize_domains(exclude_domains),
+        min_visits: min_visits.unwrap_or(1),
+        max_pages,
+    };
+    if filter.since_micros > filter.until_micros {
+        return Err("'since' must be before 'until'".to_string());
+    }
+
+    // Firefox keeps recent visits in the write-ahead log until it checkpoints,
+    // which the copy includes
+    let (browser, (graph, visits_scanned, pages_found)) = crate::read_database_copy(file_path, "history file", |conn| {
+        let browser = match browser.as_deref().map(|b| b.to_lowercase()) {
+            Some(b) if b == "chrome" || b == "chromium" || b == "edge" => Browser::Chrome,
+            Some(b) if b == "firefox" => Browser::Firefox,
+            Some(other) => return Err(format!("Unknown browser '{}'. Use chrome or firefox.", other)),
+            None => detect_browser(conn).ok_or("Not a Chrome or Firefox history database")?,
+        };
+        read_history(conn, browser, &filter).map(|read| (browser, read))
+    })?;
+
+    let db_path = crate::get_active_db_path(&app)?;
+    let mut conn = crate::open_void_db(&db_path)?;
+    let format = format!("{}-history", browser.name());
+    let merge = crate::graph_import::merge_imported_graph(&mut conn, &graph, &format)?;
+
+    Ok(HistoryImportStats {
+        browser: browser.name().to_string(),
+        visits_scanned,
+        pages_found,
+        merge,
+    })
+}
@@ -0,0 +1,614 @@
+// ============== FORCE-DIRECTED LAYOUT ==============
+//
+// A 3D spring embedder run on the backend so big voids don't have to be laid
+// out in the browser. Every node repels every other (approximated with a
+// Barnes-Hut octree, O(n log n) per step), edges pull their endpoints towards
+// a rest length, and a weak gravity keeps disconnected islands from drifting
+// off. Moves are capped by a temperature that cools over the run.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::Emitter;
+use rand::Rng;
+use rusqlite::{Connection, params};
+
+/// Event carrying intermediate positions while `run_layout` is working.
+pub const LAYOUT_PROGRESS_EVENT: &str = "layout-progress";
+
+/// Cells smaller than this stop splitting; their bodies are treated as one.
+const MAX_OCTREE_DEPTH: usize = 24;
+/// Keeps the inverse-square repulsion finite for (nearly) coincident nodes.
+const MIN_DISTANCE: f64 = 0.1;
+/// Gap kept between a relaxed node and any other node.
+pub(crate) const DEFAULT_MIN_SEPARATION: f64 = 4.0;
+/// Iterations used when only a handful of new nodes are relaxed.
+pub(crate) const INCREMENTAL_ITERATIONS: u32 = 60;
+/// How far around the relaxed nodes and the nodes they link with other nodes
+/// are loaded to push back; repulsion from further away is negligible.
+const NEIGHBORHOOD_MARGIN: f64 = 60.0;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ForceParams {
+    /// Rest length of an edge, in world units
+    pub spring_length: f64,
+    pub spring_strength: f64,
+    pub repulsion: f64,
+    /// Pull towards the origin, proportional to distance
+    pub gravity: f64,
+    /// Barnes-Hut opening angle; lower is more accurate and slower
+    pub theta: f64,
+    /// Largest move of a single node in the first step
+    pub initial_temperature: f64,
+    pub final_temperature: f64,
+}
+
+impl Default for ForceParams {
+    fn default() -> Self {
+        // Roughly matches the 8–20 unit spacing of `generate_nearby_position`
+        ForceParams {
+            spring_length: 14.0,
+            spring_strength: 0.15,
+            repulsion: 100.0,
+            gravity: 0.02,
+            theta: 0.8,
+            initial_temperature: 12.0,
+            final_temperature: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodePosition {
+    pub id: i64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutFrame {
+    pub iteration: u32,
+    pub iterations: u32,
+    pub positions: Vec<NodePosition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutResult {
+    pub node_count: i32,
+    pub edge_count: i32,
+    pub iterations: u32,
+    /// Average distance a node moved in the last iteration
+    pub final_displacement: f64,
+    pub elapsed_ms: u64,
+}
+
+pub(crate) type Vec3 = [f64; 3];
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn length(v: Vec3) -> f64 {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+// ----- Barnes-Hut octree -----
+
+struct Cell {
+    half_size: f64,
+    mass: f64,
+    center_of_mass: Vec3,
+    children: Vec<usize>,
+    /// Set for leaves holding exactly one body
+    body: Option<usize>,
+}
+
+struct Octree {
+    cells: Vec<Cell>,
+}
+
+impl Octree {
+    fn build(positions: &[Vec3]) -> Octree {
+        let mut tree = Octree { cells: Vec::with_capacity(positions.len() * 2) };
+        if positions.is_empty() {
+            return tree;
+        }
+
+        let mut min = positions[0];
+        let mut max = positions[0];
+        for p in positions {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(p[axis]);
+                max[axis] = max[axis].max(p[axis]);
+            }
+        }
+        let center = [(min[0] + max[0]) / 2.0, (min[1] + max[1]) / 2.0, (min[2] + max[2]) / 2.0];
+        let half_size = ((max[0] - min[0]).max(max[1] - min[1]).max(max[2] - min[2]) / 2.0).max(MIN_DISTANCE);
+
+        let indices: Vec<usize> = (0..positions.len()).collect();
+        tree.insert(positions, indices, center, half_size, 0);
+        tree
+    }
+
+    fn insert(&mut self, positions: &[Vec3], indices: Vec<usize>, center: Vec3, half_size: f64, depth: usize) -> usize {
+        let mass = indices.len() as f64;
+        let mut center_of_mass = [0.0; 3];
+        for &i in &indices {
+            for axis in 0..3 {
+                center_of_mass[axis] += positions[i][axis] / mass;
+            }
+        }
+
+        let cell = self.cells.len();
+        self.cells.push(Cell {
+            half_size,
+            mass,
+            center_of_mass,
+            children: vec![],
+            body: (indices.len() == 1).then(|| indices[0]),
+        });
+        if indices.len() == 1 || depth >= MAX_OCTREE_DEPTH {
+            return cell;
+        }
+
+        let mut octants: [Vec<usize>; 8] = Default::default();
+        for i in indices {
+            let p = positions[i];
+            let octant = (p[0] >= center[0]) as usize
+                | ((p[1] >= center[1]) as usize) << 1
+                | ((p[2] >= center[2]) as usize) << 2;
+            octants[octant].push(i);
+        }
+
+        let quarter = half_size / 2.0;
+        for (octant, members) in octants.into_iter().enumerate() {
+            if members.is_empty() {
+                continue;
+            }
+            let child_center = [
+                center[0] + if octant & 1 != 0 { quarter } else { -quarter },
+                center[1] + if octant & 2 != 0 { quarter } else { -quarter },
+                center[2] + if octant & 4 != 0 { quarter } else { -quarter },
+            ];
+            let child = self.insert(positions, members, child_center, quarter, depth + 1);
+            self.cells[cell].children.push(child);
+        }
+        cell
+    }
+
+    /// Repulsive force on body `i`. Far-away cells whose size/distance ratio is
+    /// below `theta` act as a single mass at their center of mass.
+    fn repulsion(&self, positions: &[Vec3], i: usize, strength: f64, theta: f64) -> Vec3 {
+        let mut force = [0.0; 3];
+        if self.cells.is_empty() {
+            return force;
+        }
+        let p = positions[i];
+        let mut stack = vec![0];
+
+        while let Some(c) = stack.pop() {
+            let cell = &self.cells[c];
+            if cell.body == Some(i) {
+                continue;
+            }
+            let delta = sub(p, cell.center_of_mass);
+            let distance = length(delta);
+
+            let is_leaf = cell.children.is_empty();
+            if !is_leaf && (cell.half_size * 2.0) / distance.max(MIN_DISTANCE) >= theta {
+                stack.extend(&cell.children);
+                continue;
+            }
+
+            // A crowded leaf may contain `i` itself; leave its own mass out
+            let mass = if is_leaf && cell.body.is_none() && distance < MIN_DISTANCE { cell.mass - 1.0 } else { cell.mass };
+            if mass <= 0.0 {
+                continue;
+            }
+            let distance = distance.max(MIN_DISTANCE);
+            let magnitude = strength * mass / (distance * distance);
+            for axis in 0..3 {
+                force[axis] += delta[axis] / distance * magnitude;
+            }
+        }
+        force
+    }
+}
+
+// ----- Simulation -----
+
+pub(crate) struct ForceLayout {
+    pub positions: Vec<Vec3>,
+    pub edges: Vec<(usize, usize)>,
+    pub params: ForceParams,
+    /// Pinned nodes still push and pull on the others but never move
+    pub movable: Vec<bool>,
+}
+
+impl ForceLayout {
+    /// `movable` marks the nodes the layout may move; the rest stay pinned.
+    pub fn new(mut positions: Vec<Vec3>, edges: Vec<(usize, usize)>, params: ForceParams, movable: Vec<bool>) -> ForceLayout {
+        // Nodes stacked on the same spot would never separate, so nudge duplicates apart
+        let mut rng = rand::thread_rng();
+        let mut seen: HashSet<[u64; 3]> = HashSet::new();
+        for (p, _) in positions.iter_mut().zip(&movable).filter(|(_, &m)| m) {
+            if !seen.insert([p[0].to_bits(), p[1].to_bits(), p[2].to_bits()]) {
+                for value in p.iter_mut() {
+                    *value += rng.gen_range(-1.0..1.0);
+                }
+            }
+        }
+        ForceLayout { positions, edges, params, movable }
+    }
+
+    /// Linear cooling from the initial to the final temperature.
+    fn temperature(&self, iteration: u32, iterations: u32) -> f64 {
+        let progress = if iterations <= 1 { 1.0 } else { iteration as f64 / (iterations - 1) as f64 };
+        self.params.initial_temperature + (self.params.final_temperature - self.params.initial_temperature) * progress
+    }
+
+    /// Runs one iteration and returns the average distance moved by the
+    /// movable nodes.
+    pub fn step(&mut self, temperature: f64) -> f64 {
+        let movable_count = self.movable.iter().filter(|&&m| m).count();
+        if movable_count == 0 {
+            return 0.0;
+        }
+        let params = self.params;
+
+        let tree = Octree::build(&self.positions);
+        let mut forces: Vec<Vec3> = (0..self.positions.len())
+            .map(|i| match self.movable[i] {
+                true => tree.repulsion(&self.positions, i, params.repulsion, params.theta),
+                false => [0.0; 3],
+            })
+            .collect();
+
+        for &(a, b) in &self.edges {
+            let delta = sub(self.positions[b], self.positions[a]);
+            let distance = length(delta).max(MIN_DISTANCE);
+            let magnitude = params.spring_strength * (distance - params.spring_length);
+            for axis in 0..3 {
+                let f = delta[axis] / distance * magnitude;
+                forces[a][axis] += f;
+                forces[b][axis] -= f;
+            }
+        }
+
+        let mut moved = 0.0;
+        for ((p, force), _) in self.positions.iter_mut().zip(&forces).zip(&self.movable).filter(|(_, &m)| m) {
+            let mut step = [
+                force[0] - params.gravity * p[0],
+                force[1] - params.gravity * p[1],
+                force[2] - params.gravity * p[2],
+            ];
+            let size = length(step);
+            if size > temperature {
+                for value in step.iter_mut() {
+                    *value *= temperature / size;
+                }
+            }
+            for axis in 0..3 {
+                p[axis] += step[axis];
+            }
+            moved += size.min(temperature);
+        }
+        moved / movable_count as f64
+    }
+
+    /// Pushes movable nodes out of each other's (and pinned nodes') way until
+    /// everything is at least `min_distance` apart, or `passes` run out.
+    pub fn separate(&mut self, min_distance: f64, passes: usize) {
+        if min_distance <= 0.0 {
+            return;
+        }
+        let mut rng = rand::thread_rng();
+        let cell_of = |p: &Vec3| {
+            [(p[0] / min_distance).floor() as i64, (p[1] / min_distance).floor() as i64, (p[2] / min_distance).floor() as i64]
+        };
+
+        for _ in 0..passes {
+            let mut grid: HashMap<[i64; 3], Vec<usize>> = HashMap::new();
+            for (i, p) in self.positions.iter().enumerate() {
+                grid.entry(cell_of(p)).or_default().push(i);
+            }
+
+            let mut overlaps = 0;
+            for i in 0..self.positions.len() {
+                if !self.movable[i] {
+                    continue;
+                }
+                let cell = cell_of(&self.positions[i]);
+                let mut push = [0.0; 3];
+                for dx in -1..=1 {
+                    for dy in -1..=1 {
+                        for dz in -1..=1 {
+                            let Some(others) = grid.get(&[cell[0] + dx, cell[1] + dy, cell[2] + dz]) else { continue };
+                            for &j in others {
+                                if j == i {
+                                    continue;
+                                }
+                                let mut delta = sub(self.positions[i], self.positions[j]);
+                                let mut distance = length(delta);
+                                if distance >= min_distance {
+                                    continue;
+                                }
+                                if distance < 1e-9 {
+                                    delta = [rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0)];
+                                    distance = length(delta).max(1e-9);
+                                }
+                                // Two movable nodes each take half of the correction
+                                let share = if self.movable[j] { 0.5 } else { 1.0 };
+                                let amount = (min_distance - distance) * share / distance;
+                                for (value, d) in push.iter_mut().zip(delta) {
+                                    *value += d * amount;
+                                }
+                                overlaps += 1;
+                            }
+                        }
+                    }
+                }
+                for (value, d) in self.positions[i].iter_mut().zip(push) {
+                    *value += d;
+                }
+            }
+            if overlaps == 0 {
+                break;
+            }
+        }
+    }
+}
+
+// ----- Loading and saving -----
+
+/// The void as the layout sees it: parallel id/position lists and edges as
+/// index pairs into them.
+pub(crate) struct LayoutGraph {
+    pub ids: Vec<i64>,
+    pub positions: Vec<Vec3>,
+    pub pinned: Vec<bool>,
+    pub edges: Vec<(usize, usize)>,
+}
+
+type NodeRow = (i64, Vec3, bool);
+
+fn query_nodes(conn: &Connection, condition: &str, bounds: &[f64]) -> Result<Vec<NodeRow>, String> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, position_x, position_y, position_z, pinned FROM nodes WHERE {} ORDER BY id",
+        condition
+    )).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(rusqlite::params_from_iter(bounds), |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            [row.get::<_, f64>(1)?, row.get::<_, f64>(2)?, row.get::<_, f64>(3)?],
+            row.get::<_, i32>(4)? == 1,
+        ))
+    }).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+fn query_edges(conn: &Connection, condition: &str) -> Result<Vec<(i64, i64)>, String> {
+    let mut stmt = conn.prepare(&format!("SELECT source_id, target_id FROM edges WHERE {}", condition))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))
+        .map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+/// Edges whose endpoints are missing or identical are dropped.
+fn build_graph(nodes: Vec<NodeRow>, edge_ids: Vec<(i64, i64)>) -> LayoutGraph {
+    let mut graph = LayoutGraph { ids: vec![], positions: vec![], pinned: vec![], edges: vec![] };
+    for (id, position, is_pinned) in nodes {
+        graph.ids.push(id);
+        graph.positions.push(position);
+        graph.pinned.push(is_pinned);
+    }
+    let index: HashMap<i64, usize> = graph.ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    for (source, target) in edge_ids {
+        if let (Some(&a), Some(&b)) = (index.get(&source), index.get(&target)) {
+            if a != b {
+                graph.edges.push((a, b));
+            }
+        }
+    }
+    graph
+}
+
+/// Loads every node and edge.
+pub(crate) fn load_graph(conn: &Connection) -> Result<LayoutGraph, String> {
+    Ok(build_graph(query_nodes(conn, "1", &[])?, query_edges(conn, "1")?))
+}
+
+fn id_list(ids: &HashSet<i64>) -> String {
+    ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Edges with at least one end in `ids`.
+fn edges_touching(conn: &Connection, ids: &HashSet<i64>) -> Result<Vec<(i64, i64)>, String> {
+    let list = id_list(ids);
+    query_edges(conn, &format!("source_id IN ({0}) OR target_id IN ({0})", list))
+}
+
+/// The part of the void an incremental relax needs: `seeds` (and with
+/// `include_neighbors` the nodes they link with), the edges that move them,
+/// and every node close enough to push on them. Returns the graph and the ids
+/// to relax.
+fn load_neighborhood(conn: &Connection, seeds: &[i64], include_neighbors: bool) -> Result<(LayoutGraph, HashSet<i64>), String> {
+    let mut selected: HashSet<i64> = seeds.iter().copied().collect();
+    let mut edges = edges_touching(conn, &selected)?;
+    if include_neighbors {
+        selected.extend(edges.iter().flat_map(|&(a, b)| [a, b]));
+        edges = edges_touching(conn, &selected)?;
+    }
+    let linked: HashSet<i64> = edges.iter().flat_map(|&(a, b)| [a, b]).chain(selected.iter().copied()).collect();
+    let linked_list = id_list(&linked);
+
+    let anchors = query_nodes(conn, &format!("id IN ({})", linked_list), &[])?;
+    if anchors.is_empty() {
+        return Ok((build_graph(vec![], vec![]), selected));
+    }
+    let mut bounds = vec![];
+    for axis in 0..3 {
+        let values = anchors.iter().map(|(_, p, _)| p[axis]);
+        let low = values.clone().fold(f64::INFINITY, f64::min);
+        let high = values.fold(f64::NEG_INFINITY, f64::max);
+        bounds.extend([low - NEIGHBORHOOD_MARGIN, high + NEIGHBORHOOD_MARGIN]);
+    }
+    let nodes = query_nodes(
+        conn,
+        &format!(
+            "id IN ({}) OR (position_x BETWEEN ?1 AND ?2 AND position_y BETWEEN ?3 AND ?4 AND position_z BETWEEN ?5 AND ?6)",
+            linked_list
+        ),
+        &bounds,
+    )?;
+    Ok((build_graph(nodes, edges), selected))
+}
+
+pub(crate) fn save_positions(conn: &mut Connection, ids: &[i64], positions: &[Vec3]) -> Result<(), String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx.prepare("UPDATE nodes SET position_x = ?1, position_y = ?2, position_z = ?3 WHERE id = ?4")
+            .map_err(|e| e.to_string())?;
+        for (id, p) in ids.iter().zip(positions) {
+            stmt.execute(params![p[0], p[1], p[2], id]).map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+pub(crate) fn frame(ids: &[i64], positions: &[Vec3], iteration: u32, iterations: u32) -> LayoutFrame {
+    LayoutFrame {
+        iteration,
+        iterations,
+        positions: ids.iter()
+            .zip(positions)
+            .map(|(&id, p)| NodePosition { id, x: p[0], y: p[1], z: p[2] })
+            .collect(),
+    }
+}
+
+/// Relaxes `node_ids` (plus their direct neighbors with `include_neighbors`)
+/// against their surroundings, which stay put, then keeps them
+/// `min_separation` away from everything. Only the neighborhood is loaded
+/// and only the moved nodes are written.
+/// Returns how many nodes moved, how many edges touch them, and their average
+/// final displacement.
+pub(crate) fn relax_nodes_in(
+    conn: &mut Connection,
+    node_ids: &[i64],
+    include_neighbors: bool,
+    iterations: u32,
+    min_separation: f64,
+) -> Result<(usize, usize, f64), String> {
+    let (LayoutGraph { ids, positions, pinned, edges }, selected_ids) = load_neighborhood(conn, node_ids, include_neighbors)?;
+    let selected: Vec<usize> = (0..ids.len()).filter(|&i| selected_ids.contains(&ids[i]) && !pinned[i]).collect();
+    if selected.is_empty() {
+        return Ok((0, 0, 0.0));
+    }
+
+    // New nodes start close to where they belong, so start cooler than a full
+    // run, and without gravity, which would drag them from an off-center
+    // parent towards the origin
+    let params = ForceParams { initial_temperature: 6.0, gravity: 0.0, ..ForceParams::default() };
+    let mut movable = vec![false; ids.len()];
+    for &i in &selected {
+        movable[i] = true;
+    }
+    let mut layout = ForceLayout::new(positions, edges, params, movable);
+
+    let mut final_displacement = 0.0;
+    for iteration in 0..iterations {
+        let temperature = layout.temperature(iteration, iterations);
+        final_displacement = layout.step(temperature);
+    }
+    layout.separate(min_separation, 10);
+    let edge_count = layout.edges.iter().filter(|&&(a, b)| layout.movable[a] || layout.movable[b]).count();
+
+    let moved_ids: Vec<i64> = selected.iter().map(|&i| ids[i]).collect();
+    let moved_positions: Vec<Vec3> = selected.iter().map(|&i| layout.positions[i]).collect();
+    save_positions(conn, &moved_ids, &moved_positions)?;
+    Ok((selected.len(), edge_count, final_displacement))
+}
+
+/// Incremental layout: moves only `node_ids` (typically nodes discovery just
+/// added) while everything else stays put, so the void doesn't reshuffle as it
+/// grows. `include_neighbors` also frees the nodes they link to or from.
+#[tauri::command]
+pub async fn relax_nodes(
+    app: tauri::AppHandle,
+    node_ids: Vec<i64>,
+    iterations: Option<u32>,
+    include_neighbors: Option<bool>,
+    min_separation: Option<f64>,
+) -> Result<LayoutResult, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let iterations = iterations.unwrap_or(INCREMENTAL_ITERATIONS).clamp(1, 5000);
+
+    std::thread::spawn(move || {
+        let started = std::time::Instant::now();
+        let mut conn = crate::open_void_db(&db_path)?;
+        let (moved, edge_count, final_displacement) = relax_nodes_in(
+            &mut conn,
+            &node_ids,
+            include_neighbors.unwrap_or(false),
+            iterations,
+            min_separation.unwrap_or(DEFAULT_MIN_SEPARATION),
+        )?;
+
+        Ok(LayoutResult {
+            node_count: moved as i32,
+            edge_count: edge_count as i32,
+            iterations,
+            final_displacement,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        })
+    }).join().map_err(|_| "Thread panic")?
+}
+
+/// Runs the force-directed layout over the whole active void and saves the
+/// result; pinned nodes hold their position. A `layout-progress` event with
+/// every node's position is emitted every `emit_every` iterations (default 10,
+/// 0 disables) so the scene can animate while the layout settles.
+#[tauri::command]
+pub async fn run_layout(
+    app: tauri::AppHandle,
+    iterations: u32,
+    emit_every: Option<u32>,
+) -> Result<LayoutResult, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let emit_every = emit_every.unwrap_or(10);
+    let iterations = iterations.clamp(1, 5000);
+
+    std::thread::spawn(move || {
+        let started = std::time::Instant::now();
+        let mut conn = crate::open_void_db(&db_path)?;
+        let LayoutGraph { ids, positions, pinned, edges } = load_graph(&conn)?;
+        let edge_count = edges.len() as i32;
+
+        let movable = pinned.iter().map(|&p| !p).collect();
+        let mut layout = ForceLayout::new(positions, edges, ForceParams::default(), movable);
+        let mut final_displacement = 0.0;
+        for iteration in 0..iterations {
+            let temperature = layout.temperature(iteration, iterations);
+            final_displacement = layout.step(temperature);
+
+            if emit_every > 0 && (iteration + 1) % emit_every == 0 && iteration + 1 < iterations {
+                let _ = app.emit(LAYOUT_PROGRESS_EVENT, frame(&ids, &layout.positions, iteration + 1, iterations));
+            }
+        }
+
+        save_positions(&mut conn, &ids, &layout.positions)?;
+        if emit_every > 0 {
+            let _ = app.emit(LAYOUT_PROGRESS_EVENT, frame(&ids, &layout.positions, iterations, iterations));
+        }
+
+        Ok(LayoutResult {
+            node_count: ids.len() as i32,
+            edge_count,
+            iterations,
+            final_displacement,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        })
+    }).join().map_err(|_| "Thread panic")?
+}
@@ -0,0 +1,355 @@
+// ============== DETERMINISTIC LAYOUTS ==============
+//
+// Layouts that depend only on the graph, so running them twice gives the same
+// picture: one sphere per domain, a hierarchical tree, concentric shells by
+// crawl depth from a root, and a timeline along the x axis by `created_at`.
+// A result can be kept as a named position set to switch back to later.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use tauri::Emitter;
+
+use crate::layout::{self, LayoutGraph, LayoutResult, Vec3};
+use crate::layouts;
+
+/// Target gap between neighboring nodes in every mode.
+const NODE_SPACING: f64 = 8.0;
+/// Gap between domain spheres, depth shells and tree levels.
+const GROUP_GAP: f64 = 24.0;
+
+const GOLDEN_ANGLE: f64 = 2.399_963_229_728_653;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LayoutMode {
+    Domain,
+    Tree,
+    Radial,
+    Timeline,
+}
+
+impl LayoutMode {
+    fn parse(mode: &str) -> Result<LayoutMode, String> {
+        match mode.to_lowercase().as_str() {
+            "domain" | "domains" => Ok(LayoutMode::Domain),
+            "tree" | "hierarchy" => Ok(LayoutMode::Tree),
+            "radial" | "depth" => Ok(LayoutMode::Radial),
+            "timeline" => Ok(LayoutMode::Timeline),
+            other => Err(format!("Unknown layout '{}'. Use domain, tree, radial or timeline.", other)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            LayoutMode::Domain => "domain",
+            LayoutMode::Tree => "tree",
+            LayoutMode::Radial => "radial",
+            LayoutMode::Timeline => "timeline",
+        }
+    }
+}
+
+/// `count` points spread evenly over a sphere, in spiral order so that
+/// consecutive points are neighbors.
+fn fibonacci_sphere(count: usize, radius: f64, center: Vec3) -> Vec<Vec3> {
+    if count == 1 {
+        return vec![center];
+    }
+    (0..count)
+        .map(|i| {
+            let y = 1.0 - 2.0 * (i as f64 + 0.5) / count as f64;
+            let ring = (1.0 - y * y).sqrt();
+            let angle = GOLDEN_ANGLE * i as f64;
+            [
+                center[0] + radius * ring * angle.cos(),
+                center[1] + radius * y,
+                center[2] + radius * ring * angle.sin(),
+            ]
+        })
+        .collect()
+}
+
+/// Sphere radius that gives `count` points roughly `NODE_SPACING` apart.
+fn sphere_radius(count: usize) -> f64 {
+    if count <= 1 {
+        0.0
+    } else {
+        NODE_SPACING * (count as f64 / (4.0 * std::f64::consts::PI)).sqrt().max(0.5)
+    }
+}
+
+fn domain_of(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_lowercase()))
+        .unwrap_or_default()
+}
+
+/// Undirected hop distance from the roots, visiting neighbors in id order.
+/// Nodes the roots can't reach start a new tree from their lowest id.
+/// Returns each node's parent (None for roots), depth and the visit order.
+fn bfs_forest(graph: &LayoutGraph, roots: &[usize]) -> (Vec<Option<usize>>, Vec<usize>, Vec<usize>) {
+    let n = graph.ids.len();
+    let mut neighbors: Vec<Vec<usize>> = vec![vec![]; n];
+    for &(a, b) in &graph.edges {
+        neighbors[a].push(b);
+        neighbors[b].push(a);
+    }
+    for list in neighbors.iter_mut() {
+        list.sort_unstable();
+        list.dedup();
+    }
+
+    let mut parent: Vec<Option<usize>> = vec![None; n];
+    let mut depth: Vec<usize> = vec![usize::MAX; n];
+    let mut order: Vec<usize> = Vec::with_capacity(n);
+
+    for root in roots.iter().copied().chain(0..n) {
+        if depth[root] != usize::MAX {
+            continue;
+        }
+        depth[root] = 0;
+        let mut queue = VecDeque::from([root]);
+        while let Some(i) = queue.pop_front() {
+            order.push(i);
+            for &j in &neighbors[i] {
+                if depth[j] == usize::MAX {
+                    depth[j] = depth[i] + 1;
+                    parent[j] = Some(i);
+                    queue.push_back(j);
+                }
+            }
+        }
+    }
+    (parent, depth, order)
+}
+
+// ----- Modes -----
+
+/// One sphere per domain. The biggest domain sits at the origin and the rest
+/// fill concentric shells around it, largest first.
+fn domain_positions(graph: &LayoutGraph, urls: &[String]) -> Vec<Vec3> {
+    let mut domains: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, url) in urls.iter().enumerate() {
+        domains.entry(domain_of(url)).or_default().push(i);
+    }
+    let mut domains: Vec<(String, Vec<usize>)> = domains.into_iter().collect();
+    domains.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+
+    let mut positions = vec![[0.0; 3]; graph.ids.len()];
+    let mut place = |members: &[usize], center: Vec3| {
+        for (&i, p) in members.iter().zip(fibonacci_sphere(members.len(), sphere_radius(members.len()), center)) {
+            positions[i] = p;
+        }
+    };
+
+    let Some((_, first)) = domains.first() else { return positions };
+    place(first, [0.0; 3]);
+
+    let mut inner_radius = sphere_radius(first.len());
+    let mut rest = &domains[1..];
+    while !rest.is_empty() {
+        // Domains are sorted by size, so the first one on a shell is the biggest
+        let widest = sphere_radius(rest[0].1.len());
+        let shell_radius = inner_radius + GROUP_GAP + widest;
+        let slot = 2.0 * widest + GROUP_GAP;
+        let capacity = ((8.0 * shell_radius * shell_radius) / (slot * slot)).floor().max(1.0) as usize;
+        let count = capacity.min(rest.len());
+
+        for ((_, members), center) in rest[..count].iter().zip(fibonacci_sphere(count, shell_radius, [0.0; 3])) {
+            place(members, center);
+        }
+        inner_radius = shell_radius + widest;
+        rest = &rest[count..];
+    }
+    positions
+}
+
+/// Layered tree in the x/y plane: depth goes down the y axis and every subtree
+/// gets horizontal room proportional to its number of leaves.
+fn tree_positions(graph: &LayoutGraph, roots: &[usize]) -> Vec<Vec3> {
+    let n = graph.ids.len();
+    let (parent, depth, order) = bfs_forest(graph, roots);
+
+    let mut children: Vec<Vec<usize>> = vec![vec![]; n];
+    for &i in &order {
+        if let Some(p) = parent[i] {
+            children[p].push(i);
+        }
+    }
+
+    // Children always come after their parent in BFS order, so walk it backwards
+    let mut leaves = vec![1usize; n];
+    for &i in order.iter().rev() {
+        if !children[i].is_empty() {
+            leaves[i] = children[i].iter().map(|&c| leaves[c]).sum();
+        }
+    }
+
+    let mut offset = vec![0usize; n];
+    let mut next_root_offset = 0;
+    for &i in &order {
+        if parent[i].is_none() {
+            offset[i] = next_root_offset;
+            next_root_offset += leaves[i];
+        }
+        let mut cursor = offset[i];
+        for &c in &children[i] {
+            offset[c] = cursor;
+            cursor += leaves[c];
+        }
+    }
+
+    let width = next_root_offset as f64 * NODE_SPACING;
+    (0..n)
+        .map(|i| [
+            (offset[i] as f64 + leaves[i] as f64 / 2.0) * NODE_SPACING - width / 2.0,
+            -(depth[i] as f64) * GROUP_GAP,
+            0.0,
+        ])
+        .collect()
+}
+
+/// Concentric spheres by hop distance from the roots. Nodes in components the
+/// roots don't reach go on one extra outer shell.
+fn radial_positions(graph: &LayoutGraph, roots: &[usize]) -> Vec<Vec3> {
+    let n = graph.ids.len();
+    let (parent, depth, order) = bfs_forest(graph, roots);
+
+    // Depths restart at 0 in every extra component, so only trust the tree
+    // grown from the first root
+    let mut tree_root: Vec<usize> = (0..n).collect();
+    for &i in &order {
+        if let Some(p) = parent[i] {
+            tree_root[i] = tree_root[p];
+        }
+    }
+    let main_root = order.first().copied();
+    let in_main: Vec<bool> = (0..n).map(|i| Some(tree_root[i]) == main_root).collect();
+
+    let max_depth = (0..n).filter(|&i| in_main[i]).map(|i| depth[i]).max().unwrap_or(0);
+    let mut shells: Vec<Vec<usize>> = vec![vec![]; max_depth + 2];
+    for &i in &order {
+        let shell = if in_main[i] { depth[i] } else { max_depth + 1 };
+        shells[shell].push(i);
+    }
+
+    let mut positions = vec![[0.0; 3]; n];
+    let mut radius: f64 = 0.0;
+    for (d, members) in shells.iter().enumerate() {
+        if members.is_empty() {
+            continue;
+        }
+        if d > 0 {
+            radius = (radius + GROUP_GAP).max(sphere_radius(members.len()));
+        }
+        for (&i, p) in members.iter().zip(fibonacci_sphere(members.len(), radius, [0.0; 3])) {
+            positions[i] = p;
+        }
+    }
+    positions
+}
+
+/// Oldest to newest along the x axis. Nodes created at about the same time
+/// spiral out around the axis instead of stacking.
+fn timeline_positions(created: &[Option<i64>]) -> Vec<Vec3> {
+    let n = created.len();
+    let known: Vec<i64> = created.iter().flatten().copied().collect();
+    let (Some(&min), Some(&max)) = (known.iter().min(), known.iter().max()) else {
+        return vec![[0.0; 3]; n];
+    };
+
+    let length = (NODE_SPACING * 6.0 * (n as f64).sqrt()).max(GROUP_GAP * 2.0);
+    let span = (max - min).max(1) as f64;
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|&i| (created[i].unwrap_or(min), i));
+
+    let mut bucket_sizes: HashMap<i64, usize> = HashMap::new();
+    let mut positions = vec![[0.0; 3]; n];
+    for i in order {
+        let x = (created[i].unwrap_or(min) - min) as f64 / span * length - length / 2.0;
+        let bucket = (x / NODE_SPACING).round() as i64;
+        let k = bucket_sizes.entry(bucket).or_insert(0);
+        let r = NODE_SPACING * (*k as f64).sqrt();
+        let angle = GOLDEN_ANGLE * *k as f64;
+        positions[i] = [x, r * angle.cos(), r * angle.sin()];
+        *k += 1;
+    }
+    positions
+}
+
+fn parse_created_at(value: &str) -> Option<i64> {
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f"))
+        .map(|dt| dt.and_utc().timestamp())
+        .ok()
+        .or_else(|| chrono::DateTime::parse_from_rfc3339(value).ok().map(|dt| dt.timestamp()))
+}
+
+/// Lays the active void out with a deterministic `mode`: `domain`, `tree`,
+/// `radial` or `timeline`. `tree` and `radial` start from `root_id`, or the
+/// oldest node. Pinned nodes keep their place. The positions are written to
+/// the nodes and, with `save_as`, also kept as a named layout.
+#[tauri::command]
+pub async fn apply_layout(
+    app: tauri::AppHandle,
+    mode: String,
+    root_id: Option<i64>,
+    save_as: Option<String>,
+) -> Result<LayoutResult, String> {
+    let mode = LayoutMode::parse(&mode)?;
+    let save_as = save_as.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    let db_path = crate::get_active_db_path(&app)?;
+
+    std::thread::spawn(move || {
+        let started = std::time::Instant::now();
+        let mut conn = crate::open_void_db(&db_path)?;
+        let graph = layout::load_graph(&conn)?;
+
+        // Same ORDER BY as `load_graph`, so rows line up with `graph.ids`
+        let mut urls: Vec<String> = Vec::with_capacity(graph.ids.len());
+        let mut created: Vec<Option<i64>> = Vec::with_capacity(graph.ids.len());
+        {
+            let mut stmt = conn.prepare("SELECT url, created_at FROM nodes ORDER BY id").map_err(|e| e.to_string())?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)))
+                .map_err(|e| e.to_string())?;
+            for (url, created_at) in rows.flatten() {
+                urls.push(url);
+                created.push(created_at.as_deref().and_then(parse_created_at));
+            }
+        }
+        if urls.len() != graph.ids.len() {
+            return Err("The void changed while laying it out, try again".to_string());
+        }
+
+        let roots: Vec<usize> = match root_id {
+            Some(id) => vec![graph.ids.iter().position(|&n| n == id).ok_or("Root node not found")?],
+            None => (0..graph.ids.len()).min_by_key(|&i| (created[i].unwrap_or(i64::MAX), i)).into_iter().collect(),
+        };
+
+        let mut positions = match mode {
+            LayoutMode::Domain => domain_positions(&graph, &urls),
+            LayoutMode::Tree => tree_positions(&graph, &roots),
+            LayoutMode::Radial => radial_positions(&graph, &roots),
+            LayoutMode::Timeline => timeline_positions(&created),
+        };
+        for (i, position) in positions.iter_mut().enumerate() {
+            if graph.pinned[i] {
+                *position = graph.positions[i];
+            }
+        }
+
+        layout::save_positions(&mut conn, &graph.ids, &positions)?;
+        if let Some(name) = &save_as {
+            layouts::save_position_set(&mut conn, name, mode.name(), &graph.ids, &positions)?;
+        }
+        let _ = app.emit(layout::LAYOUT_PROGRESS_EVENT, layout::frame(&graph.ids, &positions, 1, 1));
+
+        Ok(LayoutResult {
+            node_count: graph.ids.len() as i32,
+            edge_count: graph.edges.len() as i32,
+            iterations: 1,
+            final_displacement: 0.0,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        })
+    }).join().map_err(|_| "Thread panic")?
+}
@@ -0,0 +1,213 @@
+// ============== SAVED LAYOUTS ==============
+//
+// Nodes only have one set of `position_x/y/z` columns, so every named layout
+// is a full copy of the positions in `layout_positions`. Switching copies a
+// set back onto the nodes; nodes added after the set was saved stay where they
+// are. Pinning marks nodes that automatic layouts must not move.
+
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+use rusqlite::{Connection, params};
+
+use crate::layout::{self, Vec3};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedLayout {
+    pub id: i64,
+    pub name: String,
+    /// `manual` for snapshots, otherwise the layout mode that produced it
+    pub mode: String,
+    pub node_count: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutSwitchResult {
+    pub name: String,
+    pub nodes_moved: i32,
+    /// Nodes not in the saved set, left at their current position
+    pub nodes_missing: i32,
+}
+
+fn validate_layout_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Layout name cannot be empty".to_string());
+    }
+    Ok(name.to_string())
+}
+
+// ----- Saving -----
+
+/// Stores positions under `name`, replacing an existing set with that name.
+pub(crate) fn save_position_set(
+    conn: &mut Connection,
+    name: &str,
+    mode: &str,
+    ids: &[i64],
+    positions: &[Vec3],
+) -> Result<i64, String> {
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO layouts (name, mode) VALUES (?1, ?2)
+         ON CONFLICT(name) DO UPDATE SET mode = excluded.mode, updated_at = datetime('now')",
+        params![name, mode],
+    ).map_err(|e| e.to_string())?;
+    let layout_id: i64 = tx.query_row("SELECT id FROM layouts WHERE name = ?1", params![name], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    tx.execute("DELETE FROM layout_positions WHERE layout_id = ?1", params![layout_id])
+        .map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO layout_positions (layout_id, node_id, position_x, position_y, position_z)
+             VALUES (?1, ?2, ?3, ?4, ?5)"
+        ).map_err(|e| e.to_string())?;
+        for (id, p) in ids.iter().zip(positions) {
+            stmt.execute(params![layout_id, id, p[0], p[1], p[2]]).map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(layout_id)
+}
+
+/// Copies the current node positions into a named set.
+fn snapshot_positions(conn: &mut Connection, name: &str) -> Result<i64, String> {
+    let graph = layout::load_graph(conn)?;
+    save_position_set(conn, name, "manual", &graph.ids, &graph.positions)
+}
+
+fn query_layouts(conn: &Connection, name: Option<&str>) -> Result<Vec<SavedLayout>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT l.id, l.name, l.mode, COUNT(p.node_id), l.created_at, l.updated_at
+         FROM layouts l LEFT JOIN layout_positions p ON p.layout_id = l.id
+         WHERE ?1 IS NULL OR l.name = ?1
+         GROUP BY l.id
+         ORDER BY l.updated_at DESC, l.name"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![name], |row| {
+        Ok(SavedLayout {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            mode: row.get(2)?,
+            node_count: row.get(3)?,
+            created_at: row.get(4)?,
+            updated_at: row.get(5)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+// ----- Commands -----
+
+#[tauri::command]
+pub async fn list_layouts(app: tauri::AppHandle) -> Result<Vec<SavedLayout>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    if !db_path.exists() {
+        return Ok(vec![]);
+    }
+    let conn = crate::open_void_db(&db_path)?;
+    query_layouts(&conn, None)
+}
+
+/// Saves the void's current positions as `name`, overwriting a layout with
+/// the same name.
+#[tauri::command]
+pub async fn save_layout(app: tauri::AppHandle, name: String) -> Result<SavedLayout, String> {
+    let name = validate_layout_name(&name)?;
+    let db_path = crate::get_active_db_path(&app)?;
+    let mut conn = crate::open_void_db(&db_path)?;
+
+    snapshot_positions(&mut conn, &name)?;
+    query_layouts(&conn, Some(&name))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Failed to save layout".to_string())
+}
+
+/// Moves every node to its position in layout `name`. With `save_current_as`
+/// the positions being replaced are saved first, so nothing is lost.
+#[tauri::command]
+pub async fn switch_layout(
+    app: tauri::AppHandle,
+    name: String,
+    save_current_as: Option<String>,
+) -> Result<LayoutSwitchResult, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let mut conn = crate::open_void_db(&db_path)?;
+
+    let layout_id: i64 = conn.query_row("SELECT id FROM layouts WHERE name = ?1", params![name], |row| row.get(0))
+        .map_err(|_| format!("Layout '{}' not found", name))?;
+
+    if let Some(current) = save_current_as {
+        let current = validate_layout_name(&current)?;
+        if current == name {
+            return Err("Cannot save the current positions over the layout being switched to".to_string());
+        }
+        snapshot_positions(&mut conn, &current)?;
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let nodes_moved = tx.execute(
+        "UPDATE nodes SET
+            position_x = p.position_x,
+            position_y = p.position_y,
+            position_z = p.position_z
+         FROM layout_positions p
+         WHERE p.layout_id = ?1 AND p.node_id = nodes.id",
+        params![layout_id],
+    ).map_err(|e| e.to_string())?;
+    let node_count: i64 = tx.query_row("SELECT COUNT(*) FROM nodes", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let graph = layout::load_graph(&conn)?;
+    let _ = app.emit(layout::LAYOUT_PROGRESS_EVENT, layout::frame(&graph.ids, &graph.positions, 1, 1));
+
+    Ok(LayoutSwitchResult {
+        name,
+        nodes_moved: nodes_moved as i32,
+        nodes_missing: (node_count - nodes_moved as i64) as i32,
+    })
+}
+
+#[tauri::command]
+pub async fn delete_layout(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let mut conn = crate::open_void_db(&db_path)?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    // Foreign keys are off, so the positions do not cascade
+    tx.execute(
+        "DELETE FROM layout_positions WHERE layout_id IN (SELECT id FROM layouts WHERE name = ?1)",
+        params![name],
+    ).map_err(|e| e.to_string())?;
+    let deleted = tx.execute("DELETE FROM layouts WHERE name = ?1", params![name])
+        .map_err(|e| e.to_string())?;
+    if deleted == 0 {
+        return Err(format!("Layout '{}' not found", name));
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// Pins or unpins nodes. Pinned nodes keep their position through
+/// `run_layout`, `apply_layout` and incremental relaxing. Returns how many
+/// nodes changed.
+#[tauri::command]
+pub async fn set_nodes_pinned(app: tauri::AppHandle, node_ids: Vec<i64>, pinned: bool) -> Result<i32, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let mut conn = crate::open_void_db(&db_path)?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut changed = 0;
+    {
+        let mut stmt = tx.prepare("UPDATE nodes SET pinned = ?1 WHERE id = ?2 AND pinned != ?1")
+            .map_err(|e| e.to_string())?;
+        for id in &node_ids {
+            changed += stmt.execute(params![if pinned { 1 } else { 0 }, id]).map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(changed as i32)
+}
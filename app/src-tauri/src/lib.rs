@@ -4,10 +4,39 @@ use tauri::{Manager, WebviewUrl, WebviewWindowBuilder, Emitter};
 use std::fs;
 use std::path::PathBuf;
 use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use rand::Rng;
 use base64::{Engine as _, engine::general_purpose};
 use rusqlite::{Connection, params};
 use scraper::{Html, Selector};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+
+use crawl_log::FetchError;
+
+mod bookmarks;
+mod bundle;
+mod changes;
+mod clusters;
+mod content;
+mod crawl_log;
+mod discovery_rules;
+mod duplicates;
+mod graph_export;
+mod graph_import;
+mod history;
+mod layout;
+mod layout_modes;
+mod layouts;
+mod link_check;
+mod liveness;
+mod metrics;
+mod paths;
+mod redirects;
+mod scheduler;
+mod search;
+mod sitemap;
+mod stats;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VoidNode {
@@ -22,6 +51,281 @@ pub struct VoidNode {
     pub is_alive: bool,
     pub last_crawled: Option<String>,
     pub created_at: String,
+    /// Browser visits recorded by a history import
+    #[serde(default)]
+    pub visit_count: i64,
+    #[serde(default)]
+    pub last_visited: Option<String>,
+    /// Pinned nodes are left alone by automatic layouts
+    #[serde(default)]
+    pub pinned: bool,
+    /// Community from the last `compute_clusters` run
+    #[serde(default)]
+    pub cluster_id: Option<i64>,
+    /// The page's meta description from its last crawl
+    #[serde(default)]
+    pub description: Option<String>,
+    /// When a crawl last found different content than the one before
+    #[serde(default)]
+    pub last_changed: Option<String>,
+    /// HTTP status of the last fetch, if it got a response
+    #[serde(default)]
+    pub last_status: Option<i64>,
+    /// Why the last fetch failed, if it did (see `crawl_log`)
+    #[serde(default)]
+    pub last_error_class: Option<String>,
+    /// Node this one redirects to, if its URL is an alias (see `redirects`)
+    #[serde(default)]
+    pub canonical_node_id: Option<i64>,
+    /// Links discovery followed within the node's site to reach it (see `discovery_rules`)
+    #[serde(default)]
+    pub site_depth: Option<i64>,
+}
+
+/// Column list matching the field order expected by `void_node_from_row`.
+const VOID_NODE_COLUMNS: &str = "id, url, title, favicon, screenshot, position_x, position_y, position_z, is_alive, last_crawled, created_at, visit_count, last_visited, pinned, cluster_id, description, last_changed, last_status, last_error_class, canonical_node_id, site_depth";
+
+/// `VOID_NODE_COLUMNS` qualified with a table alias, for joins with tables
+/// that share column names.
+fn void_node_columns_as(alias: &str) -> String {
+    VOID_NODE_COLUMNS.split(", ").map(|c| format!("{}.{}", alias, c)).collect::<Vec<_>>().join(", ")
+}
+
+/// Columns added to `nodes` after the original schema. The frontend creates the
+/// table, so older voids and sessions are brought up to date on open.
+const NODE_COLUMN_MIGRATIONS: &[(&str, &str)] = &[
+    ("visit_count", "visit_count INTEGER NOT NULL DEFAULT 0"),
+    ("last_visited", "last_visited TEXT"),
+    ("pinned", "pinned INTEGER NOT NULL DEFAULT 0"),
+    ("cluster_id", "cluster_id INTEGER"),
+    ("description", "description TEXT"),
+    ("last_changed", "last_changed TEXT"),
+    ("last_status", "last_status INTEGER"),
+    ("last_error_class", "last_error_class TEXT"),
+    ("canonical_node_id", "canonical_node_id INTEGER REFERENCES nodes(id) ON DELETE SET NULL"),
+    ("site_depth", "site_depth INTEGER"),
+];
+
+/// Columns added to `edges` after the original schema.
+const EDGE_COLUMN_MIGRATIONS: &[(&str, &str)] = &[
+    ("edge_type", "edge_type TEXT NOT NULL DEFAULT 'link'"),
+];
+
+/// Columns added to `crawl_log` after it was first shipped.
+const CRAWL_LOG_COLUMN_MIGRATIONS: &[(&str, &str)] = &[
+    ("redirect_chain", "redirect_chain TEXT"),
+];
+
+/// Tables only the backend reads and writes, kept in the void so they travel
+/// with sessions.
+const VOID_EXTRA_TABLES: &str = "
+    CREATE TABLE IF NOT EXISTS layouts (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL UNIQUE,
+        mode TEXT NOT NULL,
+        created_at TEXT NOT NULL DEFAULT (datetime('now')),
+        updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+    );
+    CREATE TABLE IF NOT EXISTS layout_positions (
+        layout_id INTEGER NOT NULL REFERENCES layouts(id) ON DELETE CASCADE,
+        node_id INTEGER NOT NULL REFERENCES nodes(id) ON DELETE CASCADE,
+        position_x REAL NOT NULL,
+        position_y REAL NOT NULL,
+        position_z REAL NOT NULL,
+        PRIMARY KEY (layout_id, node_id)
+    );
+    CREATE TABLE IF NOT EXISTS clusters (
+        id INTEGER PRIMARY KEY,
+        label TEXT NOT NULL,
+        size INTEGER NOT NULL,
+        top_domain TEXT,
+        computed_at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS node_metrics (
+        node_id INTEGER PRIMARY KEY REFERENCES nodes(id) ON DELETE CASCADE,
+        in_degree INTEGER NOT NULL DEFAULT 0,
+        out_degree INTEGER NOT NULL DEFAULT 0,
+        pagerank REAL NOT NULL DEFAULT 0,
+        hub REAL NOT NULL DEFAULT 0,
+        authority REAL NOT NULL DEFAULT 0,
+        betweenness REAL NOT NULL DEFAULT 0,
+        betweenness_samples INTEGER NOT NULL DEFAULT 0,
+        computed_at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS node_content (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        node_id INTEGER NOT NULL REFERENCES nodes(id) ON DELETE CASCADE,
+        version INTEGER NOT NULL,
+        title TEXT,
+        text_gz BLOB NOT NULL,
+        html_gz BLOB,
+        text_length INTEGER NOT NULL,
+        html_length INTEGER NOT NULL,
+        fetched_at TEXT NOT NULL,
+        UNIQUE (node_id, version)
+    );
+    CREATE TABLE IF NOT EXISTS crawl_history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        node_id INTEGER NOT NULL REFERENCES nodes(id) ON DELETE CASCADE,
+        crawled_at TEXT NOT NULL,
+        title TEXT,
+        content_hash TEXT NOT NULL,
+        simhash INTEGER NOT NULL,
+        text_length INTEGER NOT NULL,
+        changed INTEGER NOT NULL DEFAULT 0,
+        similarity REAL
+    );
+    CREATE INDEX IF NOT EXISTS idx_crawl_history_node ON crawl_history(node_id);
+    CREATE INDEX IF NOT EXISTS idx_crawl_history_changed ON crawl_history(changed, crawled_at);
+    CREATE TABLE IF NOT EXISTS crawl_log (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        node_id INTEGER NOT NULL REFERENCES nodes(id) ON DELETE CASCADE,
+        crawled_at TEXT NOT NULL,
+        status_code INTEGER,
+        final_url TEXT,
+        latency_ms INTEGER NOT NULL,
+        bytes INTEGER NOT NULL DEFAULT 0,
+        error_class TEXT,
+        error TEXT,
+        redirect_chain TEXT
+    );
+    CREATE INDEX IF NOT EXISTS idx_crawl_log_node ON crawl_log(node_id);
+    CREATE INDEX IF NOT EXISTS idx_nodes_canonical ON nodes(canonical_node_id);
+    CREATE TABLE IF NOT EXISTS discovery_rules (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        rules TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+    CREATE VIRTUAL TABLE IF NOT EXISTS node_search USING fts5(
+        url, title, description, body,
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+    CREATE TRIGGER IF NOT EXISTS node_search_insert AFTER INSERT ON nodes BEGIN
+        INSERT INTO node_search (rowid, url, title, description, body)
+        VALUES (new.id, new.url, COALESCE(new.title, ''), COALESCE(new.description, ''), '');
+    END;
+    CREATE TRIGGER IF NOT EXISTS node_search_update AFTER UPDATE OF url, title, description ON nodes BEGIN
+        UPDATE node_search
+        SET url = new.url, title = COALESCE(new.title, ''), description = COALESCE(new.description, '')
+        WHERE rowid = new.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS node_search_delete AFTER DELETE ON nodes BEGIN
+        DELETE FROM node_search WHERE rowid = old.id;
+    END;";
+
+/// Fills `node_search` from `nodes` the first time it is created in a void.
+const NODE_SEARCH_BACKFILL: &str = "
+    INSERT INTO node_search (rowid, url, title, description, body)
+    SELECT id, url, COALESCE(title, ''), COALESCE(description, ''), '' FROM nodes;";
+
+/// Adds whichever of `migrations` `table` lacks. Returns false if the table
+/// does not exist.
+fn add_missing_columns(conn: &Connection, table: &str, migrations: &[(&str, &str)]) -> Result<bool, String> {
+    let columns: HashSet<String> = {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table)).map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(1)).map_err(|e| e.to_string())?;
+        rows.flatten().collect()
+    };
+    if columns.is_empty() {
+        return Ok(false);
+    }
+
+    for (name, definition) in migrations {
+        if !columns.contains(*name) {
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN {}", table, definition), [])
+                .map_err(|e| format!("Failed to add column {}: {}", name, e))?;
+        }
+    }
+    Ok(true)
+}
+
+fn migrate_void_schema(conn: &Connection) -> Result<(), String> {
+    // Nothing to migrate until the frontend has created the tables
+    if !add_missing_columns(conn, "nodes", NODE_COLUMN_MIGRATIONS)? {
+        return Ok(());
+    }
+    add_missing_columns(conn, "edges", EDGE_COLUMN_MIGRATIONS)?;
+
+    let has_search_index = conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE name = 'node_search'",
+        [],
+        |_| Ok(()),
+    ).is_ok();
+    conn.execute_batch(VOID_EXTRA_TABLES).map_err(|e| format!("Failed to create tables: {}", e))?;
+    add_missing_columns(conn, "crawl_log", CRAWL_LOG_COLUMN_MIGRATIONS)?;
+    if !has_search_index {
+        conn.execute_batch(NODE_SEARCH_BACKFILL).map_err(|e| format!("Failed to build search index: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Opens a void database and applies any pending schema migrations.
+fn open_void_db(path: &std::path::Path) -> Result<Connection, String> {
+    let conn = Connection::open(path).map_err(|e| format!("Failed to open void: {}", e))?;
+    migrate_void_schema(&conn)?;
+    Ok(conn)
+}
+
+/// Numbers the temporary copies made by `read_database_copy`, so concurrent
+/// imports never share one.
+static DATABASE_COPY_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Runs `read` on a temporary copy of the SQLite database at `path`, which a
+/// running browser keeps locked, then removes the copy. Its write-ahead log
+/// is copied too, as recent changes may still live there. `label` names the
+/// file in errors.
+pub(crate) fn read_database_copy<T>(
+    path: &std::path::Path,
+    label: &str,
+    read: impl FnOnce(&Connection) -> Result<T, String>,
+) -> Result<T, String> {
+    let temp_path = std::env::temp_dir().join(format!(
+        "void-import-{}-{}.db",
+        std::process::id(),
+        DATABASE_COPY_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp_wal = temp_path.with_extension("db-wal");
+    fs::copy(path, &temp_path).map_err(|e| format!("Failed to copy {}: {}", label, e))?;
+
+    let wal = path.with_file_name(format!("{}-wal", path.file_name().unwrap_or_default().to_string_lossy()));
+    if wal.exists() {
+        fs::copy(&wal, &temp_wal).ok();
+    }
+
+    let result = Connection::open(&temp_path)
+        .map_err(|e| format!("Failed to open {}: {}", label, e))
+        .and_then(|conn| read(&conn));
+
+    fs::remove_file(&temp_path).ok();
+    fs::remove_file(&temp_wal).ok();
+    fs::remove_file(temp_path.with_extension("db-shm")).ok();
+    result
+}
+
+fn void_node_from_row(row: &rusqlite::Row) -> rusqlite::Result<VoidNode> {
+    Ok(VoidNode {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        title: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+        favicon: row.get(3)?,
+        screenshot: row.get(4)?,
+        position_x: row.get(5)?,
+        position_y: row.get(6)?,
+        position_z: row.get(7)?,
+        is_alive: row.get::<_, i32>(8)? == 1,
+        last_crawled: row.get(9)?,
+        created_at: row.get(10)?,
+        visit_count: row.get::<_, Option<i64>>(11)?.unwrap_or(0),
+        last_visited: row.get(12)?,
+        pinned: row.get::<_, Option<i32>>(13)?.unwrap_or(0) == 1,
+        cluster_id: row.get(14)?,
+        description: row.get(15)?,
+        last_changed: row.get(16)?,
+        last_status: row.get(17)?,
+        last_error_class: row.get(18)?,
+        canonical_node_id: row.get(19)?,
+        site_depth: row.get(20)?,
+    })
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +333,13 @@ pub struct VoidEdge {
     pub id: i64,
     pub source_id: i64,
     pub target_id: i64,
+    /// `link` for a hyperlink, `redirects` from an alias to its canonical node
+    #[serde(default = "default_edge_type")]
+    pub edge_type: String,
+}
+
+fn default_edge_type() -> String {
+    redirects::LINK_EDGE_TYPE.to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -451,6 +762,12 @@ fn get_sessions_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     Ok(sessions_dir)
 }
 
+/// Path of the active void database (the working copy the frontend has open).
+fn get_active_db_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(app_data.join("void.db"))
+}
+
 #[tauri::command]
 async fn get_current_session(app: tauri::AppHandle) -> Result<String, String> {
     let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
@@ -521,6 +838,8 @@ async fn list_sessions(app: tauri::AppHandle) -> Result<Vec<SessionInfo>, String
 
 #[tauri::command]
 async fn create_new_session(app: tauri::AppHandle, name: String) -> Result<String, String> {
+    validate_session_name(&name)?;
+    
     let sessions_dir = get_sessions_dir(&app)?;
     let db_path = sessions_dir.join(format!("{}.db", name));
     
@@ -529,7 +848,21 @@ async fn create_new_session(app: tauri::AppHandle, name: String) -> Result<Strin
     }
     
     let conn = Connection::open(&db_path).map_err(|e| format!("Failed to create database: {}", e))?;
+    create_session_schema(&conn)?;
+    drop(conn);
+    
+    set_current_session_internal(&app, &name)?;
+    
+    let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    fs::create_dir_all(&app_data).map_err(|e| e.to_string())?;
+    let main_db = app_data.join("void.db");
+    fs::copy(&db_path, &main_db).map_err(|e| format!("Failed to set as active: {}", e))?;
     
+    Ok(db_path.to_string_lossy().to_string())
+}
+
+/// Creates the `nodes`/`edges` tables used by every session database.
+fn create_session_schema(conn: &Connection) -> Result<(), String> {
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS nodes (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -555,17 +888,7 @@ async fn create_new_session(app: tauri::AppHandle, name: String) -> Result<Strin
         CREATE INDEX IF NOT EXISTS idx_edges_source ON edges(source_id);
         CREATE INDEX IF NOT EXISTS idx_edges_target ON edges(target_id);"
     ).map_err(|e| format!("Failed to create tables: {}", e))?;
-    
-    drop(conn);
-    
-    set_current_session_internal(&app, &name)?;
-    
-    let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
-    fs::create_dir_all(&app_data).map_err(|e| e.to_string())?;
-    let main_db = app_data.join("void.db");
-    fs::copy(&db_path, &main_db).map_err(|e| format!("Failed to set as active: {}", e))?;
-    
-    Ok(db_path.to_string_lossy().to_string())
+    migrate_void_schema(conn)
 }
 
 #[tauri::command]
@@ -650,18 +973,359 @@ async fn load_session(app: tauri::AppHandle, path: String) -> Result<(), String>
     Ok(())
 }
 
+/// Moves a session into the trash folder instead of deleting it outright.
+/// Use `restore_deleted_session` to bring it back.
 #[tauri::command]
 async fn delete_session(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    validate_session_name(&name)?;
+    
     let sessions_dir = get_sessions_dir(&app)?;
     let session_db = sessions_dir.join(format!("{}.db", name));
     
-    if session_db.exists() {
-        fs::remove_file(&session_db).map_err(|e| e.to_string())?;
+    if !session_db.exists() {
+        return Err(format!("Session '{}' not found", name));
     }
     
+    let trash_dir = get_trash_dir(&app)?;
+    let timestamp = chrono::Local::now().format("%Y%m%d%H%M%S");
+    let trashed = trash_dir.join(format!("{}~{}.db", name, timestamp));
+    
+    fs::rename(&session_db, &trashed).map_err(|e| format!("Failed to move session to trash: {}", e))?;
+    
     Ok(())
 }
 
+// ============== SESSION RENAME / DUPLICATE / TRASH / ARCHIVE ==============
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedSessionInfo {
+    pub name: String,
+    pub path: String,
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedSessionInfo {
+    pub name: String,
+    pub path: String,
+    #[serde(rename = "archivedAt")]
+    pub archived_at: String,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
+}
+
+fn get_trash_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let trash_dir = get_sessions_dir(app)?.join("trash");
+    fs::create_dir_all(&trash_dir).map_err(|e| e.to_string())?;
+    Ok(trash_dir)
+}
+
+fn get_archive_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let archive_dir = get_sessions_dir(app)?.join("archive");
+    fs::create_dir_all(&archive_dir).map_err(|e| e.to_string())?;
+    Ok(archive_dir)
+}
+
+/// Session names become file names, so reject anything that could escape the sessions folder.
+fn validate_session_name(name: &str) -> Result<(), String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err("Session name cannot be empty".to_string());
+    }
+    if trimmed != name {
+        return Err("Session name cannot start or end with whitespace".to_string());
+    }
+    if name.starts_with('.') || name.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|']) {
+        return Err(format!("Invalid session name '{}'", name));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn rename_session(app: tauri::AppHandle, old_name: String, new_name: String) -> Result<String, String> {
+    validate_session_name(&old_name)?;
+    validate_session_name(&new_name)?;
+    
+    let sessions_dir = get_sessions_dir(&app)?;
+    let old_path = sessions_dir.join(format!("{}.db", old_name));
+    let new_path = sessions_dir.join(format!("{}.db", new_name));
+    
+    if !old_path.exists() {
+        return Err(format!("Session '{}' not found", old_name));
+    }
+    if new_path.exists() {
+        return Err(format!("Session '{}' already exists", new_name));
+    }
+    
+    fs::rename(&old_path, &new_path).map_err(|e| format!("Failed to rename session: {}", e))?;
+    
+    if get_current_session(app.clone()).await? == old_name {
+        set_current_session_internal(&app, &new_name)?;
+    }
+    
+    Ok(new_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn duplicate_session(app: tauri::AppHandle, name: String, new_name: String) -> Result<String, String> {
+    validate_session_name(&name)?;
+    validate_session_name(&new_name)?;
+    
+    let sessions_dir = get_sessions_dir(&app)?;
+    let source_path = sessions_dir.join(format!("{}.db", name));
+    let target_path = sessions_dir.join(format!("{}.db", new_name));
+    
+    if !source_path.exists() {
+        return Err(format!("Session '{}' not found", name));
+    }
+    if target_path.exists() {
+        return Err(format!("Session '{}' already exists", new_name));
+    }
+    
+    // Duplicating the session you're in should include unsaved changes from the active void.db
+    let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let main_db = app_data.join("void.db");
+    let copy_from = if get_current_session(app.clone()).await? == name && main_db.exists() {
+        main_db
+    } else {
+        source_path
+    };
+    
+    fs::copy(&copy_from, &target_path).map_err(|e| format!("Failed to duplicate session: {}", e))?;
+    
+    Ok(target_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn list_deleted_sessions(app: tauri::AppHandle) -> Result<Vec<DeletedSessionInfo>, String> {
+    let trash_dir = get_trash_dir(&app)?;
+    let mut sessions = Vec::new();
+    
+    for entry in fs::read_dir(&trash_dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+        
+        if path.extension().is_some_and(|ext| ext == "db") {
+            let stem = path.file_stem()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            
+            let (name, deleted) = match stem.rsplit_once('~') {
+                Some((name, stamp)) => (name.to_string(), chrono::NaiveDateTime::parse_from_str(stamp, "%Y%m%d%H%M%S").ok()),
+                None => (stem.clone(), None),
+            };
+            let deleted_at = deleted
+                .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "Unknown".to_string());
+            
+            sessions.push((deleted, DeletedSessionInfo {
+                name,
+                path: path.to_string_lossy().to_string(),
+                deleted_at,
+            }));
+        }
+    }
+    
+    // `deleted_at` only shows minutes, so sort on the full stamp
+    sessions.sort_by_key(|(deleted, _)| std::cmp::Reverse(*deleted));
+    
+    Ok(sessions.into_iter().map(|(_, session)| session).collect())
+}
+
+/// Restores the most recently deleted copy of a session. Fails rather than
+/// overwriting if a session with that name has been created since.
+#[tauri::command]
+async fn restore_deleted_session(app: tauri::AppHandle, name: String) -> Result<String, String> {
+    validate_session_name(&name)?;
+    
+    let deleted = list_deleted_sessions(app.clone()).await?;
+    let trashed = deleted.into_iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("No deleted session named '{}'", name))?;
+    
+    let sessions_dir = get_sessions_dir(&app)?;
+    let restored_path = sessions_dir.join(format!("{}.db", name));
+    
+    if restored_path.exists() {
+        return Err(format!("Session '{}' already exists. Rename it before restoring.", name));
+    }
+    
+    fs::rename(&trashed.path, &restored_path).map_err(|e| format!("Failed to restore session: {}", e))?;
+    
+    Ok(restored_path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn empty_session_trash(app: tauri::AppHandle) -> Result<i32, String> {
+    let trash_dir = get_trash_dir(&app)?;
+    let mut removed = 0;
+    
+    for entry in fs::read_dir(&trash_dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path.extension().is_some_and(|ext| ext == "db") {
+            fs::remove_file(&path).map_err(|e| e.to_string())?;
+            removed += 1;
+        }
+    }
+    
+    Ok(removed)
+}
+
+/// Compresses a saved session into `sessions/archive/<name>.voidz` (gzipped SQLite)
+/// and removes the uncompressed `.db`.
+fn archive_session_internal(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    let sessions_dir = get_sessions_dir(app)?;
+    let session_db = sessions_dir.join(format!("{}.db", name));
+    
+    if !session_db.exists() {
+        return Err(format!("Session '{}' not found", name));
+    }
+    
+    let archive_path = get_archive_dir(app)?.join(format!("{}.voidz", name));
+    if archive_path.exists() {
+        return Err(format!("An archive named '{}' already exists", name));
+    }
+    
+    let mut input = fs::File::open(&session_db).map_err(|e| e.to_string())?;
+    let output = fs::File::create(&archive_path).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut encoder = GzEncoder::new(output, Compression::best());
+    
+    let written = std::io::copy(&mut input, &mut encoder)
+        .and_then(|_| encoder.finish())
+        .and_then(|file| file.sync_all());
+    
+    if let Err(e) = written {
+        fs::remove_file(&archive_path).ok();
+        return Err(format!("Failed to write archive: {}", e));
+    }
+    
+    drop(input);
+    fs::remove_file(&session_db).map_err(|e| format!("Archived, but failed to remove original: {}", e))?;
+    
+    Ok(archive_path)
+}
+
+#[tauri::command]
+async fn archive_session(app: tauri::AppHandle, name: String) -> Result<String, String> {
+    validate_session_name(&name)?;
+    
+    if get_current_session(app.clone()).await? == name {
+        return Err("Cannot archive the active session".to_string());
+    }
+    
+    let archive_path = archive_session_internal(&app, &name)?;
+    Ok(archive_path.to_string_lossy().to_string())
+}
+
+/// Archives every saved session (except the active one) not modified in `older_than_days`.
+/// Returns the names of the sessions that were archived.
+#[tauri::command]
+async fn archive_old_sessions(app: tauri::AppHandle, older_than_days: i64) -> Result<Vec<String>, String> {
+    let sessions_dir = get_sessions_dir(&app)?;
+    let current = get_current_session(app.clone()).await?;
+    let cutoff = std::time::SystemTime::now()
+        - std::time::Duration::from_secs(older_than_days.max(0) as u64 * 24 * 60 * 60);
+    
+    let mut candidates = Vec::new();
+    for entry in fs::read_dir(&sessions_dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        
+        if path.extension().is_some_and(|ext| ext == "db") {
+            let name = path.file_stem()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).map_err(|e| e.to_string())?;
+            
+            if name != current && modified < cutoff {
+                candidates.push(name);
+            }
+        }
+    }
+    
+    let mut archived = Vec::new();
+    for name in candidates {
+        archive_session_internal(&app, &name)?;
+        archived.push(name);
+    }
+    
+    Ok(archived)
+}
+
+#[tauri::command]
+async fn list_archived_sessions(app: tauri::AppHandle) -> Result<Vec<ArchivedSessionInfo>, String> {
+    let archive_dir = get_archive_dir(&app)?;
+    let mut sessions = Vec::new();
+    
+    for entry in fs::read_dir(&archive_dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        
+        if path.extension().is_some_and(|ext| ext == "voidz") {
+            let metadata = fs::metadata(&path).map_err(|e| e.to_string())?;
+            let name = path.file_stem()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            
+            let modified = metadata.modified().ok();
+            let archived_at = modified
+                .map(|t| {
+                    let datetime: chrono::DateTime<chrono::Local> = t.into();
+                    datetime.format("%Y-%m-%d %H:%M").to_string()
+                })
+                .unwrap_or_else(|| "Unknown".to_string());
+            
+            sessions.push((modified, ArchivedSessionInfo {
+                name,
+                path: path.to_string_lossy().to_string(),
+                archived_at,
+                size_bytes: metadata.len(),
+            }));
+        }
+    }
+    
+    // `archived_at` only shows minutes, so sort on the full time
+    sessions.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    
+    Ok(sessions.into_iter().map(|(_, session)| session).collect())
+}
+
+#[tauri::command]
+async fn unarchive_session(app: tauri::AppHandle, name: String) -> Result<String, String> {
+    validate_session_name(&name)?;
+    
+    let archive_path = get_archive_dir(&app)?.join(format!("{}.voidz", name));
+    if !archive_path.exists() {
+        return Err(format!("No archived session named '{}'", name));
+    }
+    
+    let session_db = get_sessions_dir(&app)?.join(format!("{}.db", name));
+    if session_db.exists() {
+        return Err(format!("Session '{}' already exists", name));
+    }
+    
+    let input = fs::File::open(&archive_path).map_err(|e| e.to_string())?;
+    let mut decoder = GzDecoder::new(input);
+    let mut output = fs::File::create(&session_db).map_err(|e| e.to_string())?;
+    
+    if let Err(e) = std::io::copy(&mut decoder, &mut output) {
+        drop(output);
+        fs::remove_file(&session_db).ok();
+        return Err(format!("Failed to extract archive: {}", e));
+    }
+    drop(output);
+    
+    let valid = Connection::open(&session_db)
+        .and_then(|conn| conn.query_row("SELECT COUNT(*) FROM nodes", [], |_| Ok(())));
+    if let Err(e) = valid {
+        fs::remove_file(&session_db).ok();
+        return Err(format!("Archive does not contain a valid session: {}", e));
+    }
+    
+    fs::remove_file(&archive_path).map_err(|e| e.to_string())?;
+    
+    Ok(session_db.to_string_lossy().to_string())
+}
+
 // ============== AUTO-CRAWL SYSTEM ==============
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -671,6 +1335,19 @@ pub struct CrawlResult {
     pub favicon: Option<String>,
     pub is_alive: bool,
     pub error: Option<String>,
+    pub status_code: Option<u16>,
+    /// See `crawl_log::FetchError` and `crawl_log::status_class`
+    pub error_class: Option<String>,
+    /// Version of the content snapshot saved by this crawl
+    pub content_version: Option<i64>,
+    /// Whether the page's text differs from the previous crawl
+    pub changed: bool,
+    /// Failed crawls in a row, this one included
+    pub consecutive_failures: i64,
+    /// Node for the URL the crawl was redirected to, if not this one
+    pub canonical_node_id: Option<i64>,
+    /// The crawled node was merged into `canonical_node_id` and is gone
+    pub merged: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -680,6 +1357,12 @@ pub struct DiscoveryResult {
     pub nodes_added: i32,
     pub edges_added: i32,
     pub new_node_ids: Vec<i64>,
+    /// Node for the URL the source redirected to, which the links hang off
+    pub canonical_node_id: Option<i64>,
+    /// The source node was merged into `canonical_node_id` and is gone
+    pub merged: bool,
+    /// New links the session's discovery rules kept out
+    pub links_filtered: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -689,22 +1372,79 @@ pub struct AutoCrawlStatus {
     pub last_crawled_url: Option<String>,
 }
 
-fn fetch_page_metadata(url: &str) -> Result<(Option<String>, Option<String>, bool), String> {
+/// What a crawl learns about a page. A page that answers with an error
+/// status, or turns out to be a soft 404 or parked domain, is not alive and
+/// only has its status and final URL filled in.
+#[derive(Debug, Default)]
+struct PageMetadata {
+    title: Option<String>,
+    favicon: Option<String>,
+    description: Option<String>,
+    is_alive: bool,
+    links: Vec<String>,
+    html: String,
+    status: Option<u16>,
+    /// Where redirects ended up
+    final_url: Option<String>,
+    /// URLs redirected from on the way to `final_url`, the requested one first;
+    /// empty when there were no redirects
+    redirect_chain: Vec<String>,
+    /// Set when the page loaded but is a soft 404 or a parked domain
+    dead_reason: Option<&'static str>,
+    bytes: usize,
+}
+
+/// The page's `<meta name="description">`, falling back to `og:description`.
+fn page_description(document: &Html) -> Option<String> {
+    [r#"meta[name="description"]"#, r#"meta[property="og:description"]"#]
+        .iter()
+        .filter_map(|s| Selector::parse(s).ok())
+        .find_map(|selector| {
+            document.select(&selector)
+                .filter_map(|el| el.value().attr("content"))
+                .map(|c| c.split_whitespace().collect::<Vec<_>>().join(" "))
+                .find(|c| !c.is_empty())
+        })
+        .map(|c| c.chars().take(1000).collect())
+}
+
+fn fetch_page_metadata(url: &str) -> Result<PageMetadata, FetchError> {
+    let hops = Arc::new(Mutex::new(vec![]));
     let client = reqwest::blocking::Client::builder()
         .timeout(std::time::Duration::from_secs(10))
         .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+        .redirect(redirects::recording_policy(10, hops.clone()))
         .build()
         .map_err(|e| e.to_string())?;
     
-    let response = client.get(url).send().map_err(|e| e.to_string())?;
+    let response = client.get(url).send()?;
+    let status = response.status().as_u16();
+    let redirect_chain = redirects::take_hops(&hops);
     
     if !response.status().is_success() {
-        return Ok((None, None, false));
+        return Ok(PageMetadata {
+            status: Some(status),
+            final_url: Some(response.url().to_string()),
+            redirect_chain,
+            ..Default::default()
+        });
     }
     
-    let html = response.text().map_err(|e| e.to_string())?;
+    let final_url = response.url().clone();
+    let html = response.text()?;
     let document = Html::parse_document(&html);
     
+    if let Some(reason) = liveness::dead_page_reason(url, final_url.as_str(), &document, &html) {
+        return Ok(PageMetadata {
+            status: Some(status),
+            final_url: Some(final_url.to_string()),
+            redirect_chain,
+            dead_reason: Some(reason),
+            bytes: html.len(),
+            ..Default::default()
+        });
+    }
+    
     let title_selector = Selector::parse("title").unwrap();
     let title = document.select(&title_selector)
         .next()
@@ -745,27 +1485,72 @@ fn fetch_page_metadata(url: &str) -> Result<(Option<String>, Option<String>, boo
         found_favicon.or_else(|| Some(format!("{}/favicon.ico", base_url)))
     };
     
-    Ok((title, favicon, true))
+    Ok(PageMetadata {
+        title,
+        favicon,
+        description: page_description(&document),
+        is_alive: true,
+        links: vec![],
+        status: Some(status),
+        final_url: Some(final_url.to_string()),
+        redirect_chain,
+        dead_reason: None,
+        bytes: html.len(),
+        html,
+    })
 }
 
-fn fetch_page_metadata_with_links(url: &str) -> Result<(Option<String>, Option<String>, bool, Vec<String>), String> {
+/// Normalizes a discovered link the way it is stored as a node URL: http(s)
+/// only, no fragment, no trailing slash.
+fn clean_link(href: &str) -> Option<String> {
+    if !(href.starts_with("http://") || href.starts_with("https://")) {
+        return None;
+    }
+    let mut parsed = url::Url::parse(href).ok()?;
+    parsed.set_fragment(None);
+    let clean_url = parsed.to_string().trim_end_matches('/').to_string();
+    (clean_url.len() < 500).then_some(clean_url)
+}
+
+/// Like `fetch_page_metadata`, but also collects the page's links. With
+/// `include_feeds`, items of any RSS/Atom feed the page advertises are added too.
+fn fetch_page_metadata_with_links(url: &str, include_feeds: bool) -> Result<PageMetadata, FetchError> {
+    let hops = Arc::new(Mutex::new(vec![]));
     let client = reqwest::blocking::Client::builder()
         .timeout(std::time::Duration::from_secs(10))
         .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
-        .redirect(reqwest::redirect::Policy::limited(5))
+        .redirect(redirects::recording_policy(5, hops.clone()))
         .build()
         .map_err(|e| e.to_string())?;
     
-    let response = client.get(url).send().map_err(|e| e.to_string())?;
+    let response = client.get(url).send()?;
+    let status = response.status().as_u16();
+    let redirect_chain = redirects::take_hops(&hops);
     
     if !response.status().is_success() {
-        return Ok((None, None, false, vec![]));
+        return Ok(PageMetadata {
+            status: Some(status),
+            final_url: Some(response.url().to_string()),
+            redirect_chain,
+            ..Default::default()
+        });
     }
     
     let final_url = response.url().clone();
-    let html = response.text().map_err(|e| e.to_string())?;
+    let html = response.text()?;
     let document = Html::parse_document(&html);
     
+    if let Some(reason) = liveness::dead_page_reason(url, final_url.as_str(), &document, &html) {
+        return Ok(PageMetadata {
+            status: Some(status),
+            final_url: Some(final_url.to_string()),
+            redirect_chain,
+            dead_reason: Some(reason),
+            bytes: html.len(),
+            ..Default::default()
+        });
+    }
+    
     let title_selector = Selector::parse("title").unwrap();
     let title = document.select(&title_selector)
         .next()
@@ -821,20 +1606,38 @@ fn fetch_page_metadata_with_links(url: &str) -> Result<(Option<String>, Option<S
                     continue;
                 };
                 
-                if normalized.starts_with("http://") || normalized.starts_with("https://") {
-                    if let Ok(mut parsed) = url::Url::parse(&normalized) {
-                        parsed.set_fragment(None);
-                        let clean_url = parsed.to_string().trim_end_matches('/').to_string();
-                        if !links.contains(&clean_url) && clean_url.len() < 500 {
-                            links.push(clean_url);
-                        }
+                if let Some(clean_url) = clean_link(&normalized) {
+                    if !links.contains(&clean_url) {
+                        links.push(clean_url);
                     }
                 }
             }
         }
     }
     
-    Ok((title, favicon, true, links))
+    if include_feeds {
+        for feed_url in sitemap::feed_urls(&document, &final_url) {
+            for clean_url in sitemap::fetch_feed_links(&client, &feed_url).iter().filter_map(|l| clean_link(l)) {
+                if !links.contains(&clean_url) {
+                    links.push(clean_url);
+                }
+            }
+        }
+    }
+    
+    Ok(PageMetadata {
+        title,
+        favicon,
+        description: page_description(&document),
+        is_alive: true,
+        links,
+        status: Some(status),
+        final_url: Some(final_url.to_string()),
+        redirect_chain,
+        dead_reason: None,
+        bytes: html.len(),
+        html,
+    })
 }
 
 fn generate_nearby_position(source_x: f64, source_y: f64, source_z: f64) -> (f64, f64, f64) {
@@ -850,6 +1653,7 @@ fn generate_nearby_position(source_x: f64, source_y: f64, source_z: f64) -> (f64
     (x, y, z)
 }
 
+/// The node most in need of a crawl, by the scores of `scheduler`.
 #[tauri::command]
 async fn get_next_crawl_target(app: tauri::AppHandle, stale_days: i32) -> Result<Option<VoidNode>, String> {
     let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
@@ -859,47 +1663,31 @@ async fn get_next_crawl_target(app: tauri::AppHandle, stale_days: i32) -> Result
         return Ok(None);
     }
     
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
-    
-    let query = format!(
-        "SELECT id, url, title, favicon, screenshot, position_x, position_y, position_z, is_alive, last_crawled, created_at 
-         FROM nodes 
-         WHERE last_crawled IS NULL 
-            OR last_crawled < datetime('now', '-{} days')
-         ORDER BY 
-            CASE WHEN last_crawled IS NULL THEN 0 ELSE 1 END,
-            last_crawled ASC
-         LIMIT 1",
-        stale_days
-    );
+    let conn = open_void_db(&db_path)?;
     
-    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
-    
-    let node = stmt.query_row([], |row| {
-        Ok(VoidNode {
-            id: row.get(0)?,
-            url: row.get(1)?,
-            title: row.get(2)?,
-            favicon: row.get(3)?,
-            screenshot: row.get(4)?,
-            position_x: row.get(5)?,
-            position_y: row.get(6)?,
-            position_z: row.get(7)?,
-            is_alive: row.get::<_, i32>(8)? == 1,
-            last_crawled: row.get(9)?,
-            created_at: row.get(10)?,
-        })
-    }).ok();
-    
-    Ok(node)
+    let batch = scheduler::crawl_batch_in(&conn, 1, stale_days as i64, None)?;
+    Ok(batch.into_iter().next().map(|target| target.node))
 }
 
+/// Refreshes a node's title, favicon, description and liveness. With
+/// `save_content`, the page's readable text and HTML are kept as a new
+/// content version (see `get_node_content`). If the URL redirects to a live
+/// page elsewhere, it is stored on the node for the final URL and the crawled
+/// node becomes its alias, or is merged into it with `merge_redirects`.
+/// Transient failures are retried, and the node is only marked dead after
+/// `failure_threshold` (default 3) failed crawls in a row.
 #[tauri::command]
-async fn crawl_single_node(app: tauri::AppHandle, node_id: i64) -> Result<CrawlResult, String> {
+async fn crawl_single_node(
+    app: tauri::AppHandle,
+    node_id: i64,
+    save_content: Option<bool>,
+    merge_redirects: Option<bool>,
+    failure_threshold: Option<i64>,
+) -> Result<CrawlResult, String> {
     let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
     let db_path = app_data.join("void.db");
     
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let mut conn = open_void_db(&db_path)?;
     
     let url: String = conn.query_row(
         "SELECT url FROM nodes WHERE id = ?",
@@ -907,47 +1695,79 @@ async fn crawl_single_node(app: tauri::AppHandle, node_id: i64) -> Result<CrawlR
         |row| row.get(0)
     ).map_err(|e| format!("Node not found: {}", e))?;
     
+    let started = std::time::Instant::now();
     let result = std::thread::spawn(move || {
-        fetch_page_metadata(&url)
+        liveness::with_retries(|| fetch_page_metadata(&url))
     }).join().map_err(|_| "Thread panic")?;
+    crawl_log::log_fetch(&conn, node_id, &result, started.elapsed().as_millis())?;
+    let threshold = failure_threshold.unwrap_or(liveness::DEFAULT_FAILURE_THRESHOLD);
     
     match result {
-        Ok((title, favicon, is_alive)) => {
+        Ok(page) => {
+            let redirect = redirects::resolve_redirect(&mut conn, node_id, &page, merge_redirects.unwrap_or(false))?;
+            let page_node_id = redirect.page_node_id;
+            // A merged node's fetch log moved to the node it was merged into
+            let crawled_id = if redirect.merged { page_node_id } else { node_id };
+            let (is_alive, consecutive_failures) = liveness::update_liveness(&conn, crawled_id, threshold)?;
             conn.execute(
                 "UPDATE nodes SET 
                     title = COALESCE(?, title),
                     favicon = COALESCE(?, favicon),
-                    is_alive = ?,
+                    description = COALESCE(?, description),
+                    is_alive = CASE WHEN ? THEN 1 ELSE is_alive END,
                     last_crawled = datetime('now')
                  WHERE id = ?",
                 params![
-                    title,
-                    favicon,
-                    if is_alive { 1 } else { 0 },
-                    node_id
+                    page.title,
+                    page.favicon,
+                    page.description,
+                    if page.is_alive { 1 } else { 0 },
+                    page_node_id
                 ]
             ).map_err(|e| e.to_string())?;
             
+            let mut content_version = None;
+            let mut changed = false;
+            if page.is_alive {
+                let text = content::extract_readable_text(&Html::parse_document(&page.html));
+                changed = changes::record_crawl(&conn, page_node_id, page.title.as_deref(), &text)?.changed;
+                if save_content.unwrap_or(false) {
+                    content_version = Some(content::store_snapshot(&conn, page_node_id, page.title.as_deref(), &page.html, &text)?);
+                }
+            }
+            
+            let error_class = crawl_log::page_class(&page).map(String::from);
             Ok(CrawlResult {
                 node_id,
-                title,
-                favicon,
+                title: page.title,
+                favicon: page.favicon,
                 is_alive,
                 error: None,
+                status_code: page.status,
+                error_class,
+                content_version,
+                changed,
+                consecutive_failures,
+                canonical_node_id: (page_node_id != node_id).then_some(page_node_id),
+                merged: redirect.merged,
             })
         },
         Err(e) => {
-            conn.execute(
-                "UPDATE nodes SET is_alive = 0, last_crawled = datetime('now') WHERE id = ?",
-                params![node_id]
-            ).map_err(|e| e.to_string())?;
+            let (is_alive, consecutive_failures) = liveness::update_liveness(&conn, node_id, threshold)?;
             
             Ok(CrawlResult {
                 node_id,
                 title: None,
                 favicon: None,
-                is_alive: false,
-                error: Some(e),
+                is_alive,
+                error: Some(e.message),
+                status_code: None,
+                error_class: Some(e.class.to_string()),
+                content_version: None,
+                changed: false,
+                consecutive_failures,
+                canonical_node_id: None,
+                merged: false,
             })
         }
     }
@@ -969,12 +1789,10 @@ async fn get_auto_crawl_status(app: tauri::AppHandle, stale_days: i32) -> Result
     let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
     
     let pending: i32 = conn.query_row(
-        &format!(
-            "SELECT COUNT(*) FROM nodes 
-             WHERE last_crawled IS NULL OR last_crawled < datetime('now', '-{} days')",
-            stale_days
-        ),
-        [],
+        "SELECT COUNT(*) FROM nodes 
+         WHERE (last_crawled IS NULL OR last_crawled < datetime('now', ?))
+            AND url LIKE 'http%'",
+        params![format!("-{} days", stale_days)],
         |row| row.get(0)
     ).unwrap_or(0);
     
@@ -1010,17 +1828,26 @@ async fn reset_all_crawl_timestamps(app: tauri::AppHandle) -> Result<i32, String
     Ok(count as i32)
 }
 
+/// Fetches a node and adds the pages it links to. Links to an alias are
+/// attached to its canonical node; redirects of the source itself are
+/// handled as in `crawl_single_node`. New pages must pass the session's
+/// discovery rules.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn discover_links_from_node(
     app: tauri::AppHandle, 
     node_id: i64,
     max_new_nodes: i32,
     external_only: bool,
+    include_feeds: Option<bool>,
+    include_sitemaps: Option<bool>,
+    relax_layout: Option<bool>,
+    merge_redirects: Option<bool>,
 ) -> Result<DiscoveryResult, String> {
     let app_data = app.path().app_data_dir().map_err(|e| e.to_string())?;
     let db_path = app_data.join("void.db");
     
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let mut conn = open_void_db(&db_path)?;
     
     let (source_url, source_x, source_y, source_z): (String, f64, f64, f64) = conn.query_row(
         "SELECT url, position_x, position_y, position_z FROM nodes WHERE id = ?",
@@ -1028,36 +1855,58 @@ async fn discover_links_from_node(
         |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
     ).map_err(|e| format!("Source node not found: {}", e))?;
     
-    let source_domain = url::Url::parse(&source_url)
-        .ok()
-        .and_then(|u| u.host_str().map(|h| h.to_string()))
-        .unwrap_or_default();
-    
     let url_clone = source_url.clone();
-    let fetch_result = std::thread::spawn(move || {
-        fetch_page_metadata_with_links(&url_clone)
+    let (fetch_result, latency) = std::thread::spawn(move || {
+        let started = std::time::Instant::now();
+        let mut result = liveness::with_retries(|| fetch_page_metadata_with_links(&url_clone, include_feeds.unwrap_or(false)));
+        let latency = started.elapsed().as_millis();
+        if let Some(page) = result.as_mut().ok().filter(|p| p.is_alive && include_sitemaps.unwrap_or(false)) {
+            for link in sitemap::site_page_urls(&url_clone, sitemap::DISCOVERY_SITEMAP_LIMIT).iter().filter_map(|l| clean_link(l)) {
+                if !page.links.contains(&link) {
+                    page.links.push(link);
+                }
+            }
+        }
+        (result, latency)
     }).join().map_err(|_| "Thread panic")?;
+    crawl_log::log_fetch(&conn, node_id, &fetch_result, latency).ok();
     
-    let (title, favicon, is_alive, links) = match fetch_result {
+    let page = match fetch_result {
         Ok(result) => result,
         Err(e) => {
-            conn.execute(
-                "UPDATE nodes SET is_alive = 0, last_crawled = datetime('now') WHERE id = ?",
-                params![node_id]
-            ).ok();
-            return Err(e);
+            liveness::update_liveness(&conn, node_id, liveness::DEFAULT_FAILURE_THRESHOLD).ok();
+            return Err(e.message);
         }
     };
+    let redirect = redirects::resolve_redirect(&mut conn, node_id, &page, merge_redirects.unwrap_or(false))?;
+    let page_node_id = redirect.page_node_id;
+    let crawled_id = if redirect.merged { page_node_id } else { node_id };
+    liveness::update_liveness(&conn, crawled_id, liveness::DEFAULT_FAILURE_THRESHOLD)?;
+    
+    // Links are relative to where the redirects ended, not the node's old URL
+    let source_domain = url::Url::parse(page.final_url.as_deref().unwrap_or(&source_url))
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+        .unwrap_or_default();
     
     conn.execute(
         "UPDATE nodes SET 
             title = COALESCE(?, title),
             favicon = COALESCE(?, favicon),
-            is_alive = ?,
+            description = COALESCE(?, description),
+            is_alive = CASE WHEN ? THEN 1 ELSE is_alive END,
             last_crawled = datetime('now')
          WHERE id = ?",
-        params![title, favicon, if is_alive { 1 } else { 0 }, node_id]
+        params![page.title, page.favicon, page.description, if page.is_alive { 1 } else { 0 }, page_node_id]
     ).map_err(|e| e.to_string())?;
+    if page.is_alive {
+        let text = content::extract_readable_text(&Html::parse_document(&page.html));
+        changes::record_crawl(&conn, page_node_id, page.title.as_deref(), &text)?;
+    }
+    
+    let rules = discovery_rules::load_rule_set(&conn)?;
+    let source_depth = discovery_rules::site_depth(&conn, page_node_id)?;
+    let page_url = page.final_url.clone().unwrap_or_else(|| source_url.clone());
     
     let mut existing_urls: HashSet<String> = HashSet::new();
     {
@@ -1072,24 +1921,25 @@ async fn discover_links_from_node(
     
     let mut nodes_added = 0;
     let mut edges_added = 0;
+    let mut links_filtered = 0;
     let mut new_node_ids: Vec<i64> = vec![];
     
-    for link in links.iter() {
+    for link in page.links.iter() {
         if nodes_added >= max_new_nodes {
             break;
         }
         
         if existing_urls.contains(link) {
             let target_id: Option<i64> = conn.query_row(
-                "SELECT id FROM nodes WHERE url = ?",
+                "SELECT COALESCE(canonical_node_id, id) FROM nodes WHERE url = ?",
                 params![link],
                 |row| row.get(0)
             ).ok();
             
-            if let Some(tid) = target_id {
+            if let Some(tid) = target_id.filter(|&tid| tid != page_node_id) {
                 let result = conn.execute(
                     "INSERT OR IGNORE INTO edges (source_id, target_id) VALUES (?, ?)",
-                    params![node_id, tid]
+                    params![page_node_id, tid]
                 );
                 if result.is_ok() && result.unwrap() > 0 {
                     edges_added += 1;
@@ -1108,6 +1958,14 @@ async fn discover_links_from_node(
             }
         }
         
+        let depth = match rules.link_depth(&page_url, source_depth, link) {
+            Some(depth) if rules.allows(link) => depth,
+            _ => {
+                links_filtered += 1;
+                continue;
+            }
+        };
+        
         let (x, y, z) = generate_nearby_position(source_x, source_y, source_z);
         
         let domain = url::Url::parse(link)
@@ -1116,9 +1974,9 @@ async fn discover_links_from_node(
             .unwrap_or_else(|| "Unknown".to_string());
         
         let insert_result = conn.execute(
-            "INSERT INTO nodes (url, title, position_x, position_y, position_z, is_alive, created_at) 
-             VALUES (?, ?, ?, ?, ?, 1, datetime('now'))",
-            params![link, domain, x, y, z]
+            "INSERT INTO nodes (url, title, position_x, position_y, position_z, is_alive, created_at, site_depth) 
+             VALUES (?, ?, ?, ?, ?, 1, datetime('now'), ?)",
+            params![link, domain, x, y, z, depth]
         );
         
         if let Ok(_) = insert_result {
@@ -1129,18 +1987,26 @@ async fn discover_links_from_node(
             
             conn.execute(
                 "INSERT OR IGNORE INTO edges (source_id, target_id) VALUES (?, ?)",
-                params![node_id, new_id]
+                params![page_node_id, new_id]
             ).ok();
             edges_added += 1;
         }
     }
     
+    // Spread the new children out without disturbing the rest of the void
+    if relax_layout.unwrap_or(true) && !new_node_ids.is_empty() {
+        layout::relax_nodes_in(&mut conn, &new_node_ids, false, layout::INCREMENTAL_ITERATIONS, layout::DEFAULT_MIN_SEPARATION).ok();
+    }
+    
     Ok(DiscoveryResult {
         source_node_id: node_id,
-        links_found: links.len() as i32,
+        links_found: page.links.len() as i32,
         nodes_added,
         edges_added,
         new_node_ids,
+        canonical_node_id: (page_node_id != node_id).then_some(page_node_id),
+        merged: redirect.merged,
+        links_filtered,
     })
 }
 
@@ -1153,30 +2019,19 @@ async fn get_random_discovery_target(app: tauri::AppHandle) -> Result<Option<Voi
         return Ok(None);
     }
     
-    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+    let conn = open_void_db(&db_path)?;
     
     let node = conn.query_row(
-        "SELECT id, url, title, favicon, screenshot, position_x, position_y, position_z, is_alive, last_crawled, created_at 
-         FROM nodes 
-         WHERE is_alive = 1
-         ORDER BY RANDOM()
-         LIMIT 1",
+        &format!(
+            "SELECT {} 
+             FROM nodes 
+             WHERE is_alive = 1 AND url LIKE 'http%'
+             ORDER BY RANDOM()
+             LIMIT 1",
+            VOID_NODE_COLUMNS
+        ),
         [],
-        |row| {
-            Ok(VoidNode {
-                id: row.get(0)?,
-                url: row.get(1)?,
-                title: row.get(2)?,
-                favicon: row.get(3)?,
-                screenshot: row.get(4)?,
-                position_x: row.get(5)?,
-                position_y: row.get(6)?,
-                position_z: row.get(7)?,
-                is_alive: row.get::<_, i32>(8)? == 1,
-                last_crawled: row.get(9)?,
-                created_at: row.get(10)?,
-            })
-        }
+        void_node_from_row
     ).ok();
     
     Ok(node)
@@ -1231,6 +2086,54 @@ pub fn run() {
             save_session_as,
             load_session,
             delete_session,
+            rename_session,
+            duplicate_session,
+            list_deleted_sessions,
+            restore_deleted_session,
+            empty_session_trash,
+            archive_session,
+            archive_old_sessions,
+            list_archived_sessions,
+            unarchive_session,
+            bundle::export_void_bundle,
+            bundle::inspect_void_bundle,
+            bundle::import_void_bundle,
+            graph_export::export_graph,
+            graph_import::import_graph_file,
+            bookmarks::import_bookmarks,
+            history::import_browser_history,
+            sitemap::seed_from_sitemap,
+            layout::run_layout,
+            layout::relax_nodes,
+            layout_modes::apply_layout,
+            layouts::list_layouts,
+            layouts::save_layout,
+            layouts::switch_layout,
+            layouts::delete_layout,
+            layouts::set_nodes_pinned,
+            clusters::compute_clusters,
+            metrics::compute_graph_metrics,
+            metrics::get_graph_metrics,
+            metrics::get_node_metrics,
+            stats::get_void_stats,
+            paths::find_path,
+            paths::get_neighborhood,
+            paths::connected_components,
+            search::search_nodes,
+            content::get_node_content,
+            content::list_node_content,
+            changes::list_changed_nodes,
+            changes::get_change_history,
+            crawl_log::get_crawl_log,
+            redirects::get_redirect_aliases,
+            liveness::check_node_liveness,
+            link_check::check_all_links,
+            scheduler::get_crawl_batch,
+            duplicates::find_duplicate_nodes,
+            duplicates::merge_nodes,
+            discovery_rules::get_discovery_rules,
+            discovery_rules::set_discovery_rules,
+            discovery_rules::reset_discovery_rules,
             get_next_crawl_target,
             crawl_single_node,
             get_auto_crawl_status,
@@ -0,0 +1,235 @@
+// ============== LINK CHECK ==============
+//
+// Sweeps every node's URL for liveness, like a link checker over the whole
+// void. Worker threads each take one host at a time and check its URLs one
+// after another with a pause in between, so no site sees more than one
+// request at once. Results go back to a single writer that logs them, emits
+// a `link-check-progress` event per node and builds the dead-link report.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use rusqlite::{Connection, params};
+
+use crate::liveness::{self, LivenessResult};
+
+pub const LINK_CHECK_PROGRESS_EVENT: &str = "link-check-progress";
+
+const DEFAULT_CONCURRENCY: usize = 8;
+const MAX_CONCURRENCY: usize = 32;
+const DEFAULT_STALE_DAYS: i64 = 7;
+/// Pause between two requests to the same host.
+const HOST_DELAY_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkCheckProgress {
+    pub checked: i32,
+    pub total: i32,
+    pub url: String,
+    pub result: LivenessResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLink {
+    pub node_id: i64,
+    pub url: String,
+    pub title: String,
+    pub status_code: Option<u16>,
+    pub error_class: Option<String>,
+    pub error: Option<String>,
+    pub consecutive_failures: i64,
+    /// Stays true until the node has failed enough checks in a row
+    pub is_alive: bool,
+    /// Nodes linking to this one, so the broken references can be found
+    pub linked_from: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkCheckReport {
+    pub checked: i32,
+    pub reachable: i32,
+    pub unreachable: i32,
+    /// Nodes this sweep marked dead that were alive before
+    pub newly_dead: i32,
+    /// Failed checks per error class
+    pub by_error_class: BTreeMap<String, i32>,
+    /// Nodes that failed this check, most failures first
+    pub dead_links: Vec<DeadLink>,
+    pub elapsed_ms: u64,
+}
+
+struct Target {
+    id: i64,
+    url: String,
+    title: String,
+    was_alive: bool,
+}
+
+fn load_targets(conn: &Connection, stale_days: Option<i64>) -> Result<Vec<Target>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT id, url, COALESCE(title, ''), is_alive FROM nodes
+         WHERE url LIKE 'http%'
+           AND (?1 IS NULL OR last_crawled IS NULL OR last_crawled < datetime('now', ?1))
+         ORDER BY id"
+    ).map_err(|e| e.to_string())?;
+    let cutoff = stale_days.map(|days| format!("-{} days", days));
+    let rows = stmt.query_map(params![cutoff], |row| {
+        Ok(Target {
+            id: row.get(0)?,
+            url: row.get(1)?,
+            title: row.get(2)?,
+            was_alive: row.get::<_, i32>(3)? == 1,
+        })
+    }).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+/// Targets grouped by host, busiest hosts first so they start early.
+fn host_queues(targets: &[Target]) -> VecDeque<Vec<usize>> {
+    let mut by_host: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, target) in targets.iter().enumerate() {
+        let host = url::Url::parse(&target.url)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+            .unwrap_or_default();
+        by_host.entry(host).or_default().push(i);
+    }
+    let mut queues: Vec<Vec<usize>> = by_host.into_values().collect();
+    queues.sort_by_key(|q| std::cmp::Reverse(q.len()));
+    queues.into()
+}
+
+fn linked_from(conn: &Connection, node_id: i64) -> Result<Vec<i64>, String> {
+    let mut stmt = conn.prepare("SELECT source_id FROM edges WHERE target_id = ?1 ORDER BY source_id")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![node_id], |row| row.get(0)).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+/// Sweeps the void, or only nodes not crawled within `stale_days`, calling
+/// `on_progress` after each node is recorded.
+pub(crate) fn check_all_links_in(
+    conn: &Connection,
+    concurrency: usize,
+    stale_days: Option<i64>,
+    inspect_pages: bool,
+    threshold: i64,
+    mut on_progress: impl FnMut(LinkCheckProgress),
+) -> Result<LinkCheckReport, String> {
+    let started = Instant::now();
+    let targets = Arc::new(load_targets(conn, stale_days)?);
+    let queues = Arc::new(Mutex::new(host_queues(&targets)));
+    let (sender, receiver) = mpsc::channel();
+
+    let workers: Vec<_> = (0..concurrency).map(|_| {
+        let targets = targets.clone();
+        let queues = queues.clone();
+        let sender = sender.clone();
+        std::thread::spawn(move || loop {
+            let next = queues.lock().ok().and_then(|mut q| q.pop_front());
+            let Some(queue) = next else {
+                break;
+            };
+            for (n, &i) in queue.iter().enumerate() {
+                if n > 0 {
+                    std::thread::sleep(Duration::from_millis(HOST_DELAY_MS));
+                }
+                let checked_at = Instant::now();
+                let result = liveness::with_retries(|| liveness::check_url(&targets[i].url, inspect_pages));
+                if sender.send((i, result, checked_at.elapsed().as_millis())).is_err() {
+                    return;
+                }
+            }
+        })
+    }).collect();
+    // The workers hold the remaining senders; the loop below ends with them
+    drop(sender);
+
+    let total = targets.len() as i32;
+    let mut report = LinkCheckReport {
+        checked: 0,
+        reachable: 0,
+        unreachable: 0,
+        newly_dead: 0,
+        by_error_class: BTreeMap::new(),
+        dead_links: vec![],
+        elapsed_ms: 0,
+    };
+    for (i, result, latency) in receiver {
+        let target = &targets[i];
+        let result = liveness::record_check(conn, target.id, result, latency, threshold)?;
+        report.checked += 1;
+
+        if result.reachable {
+            report.reachable += 1;
+        } else {
+            report.unreachable += 1;
+            if target.was_alive && !result.is_alive {
+                report.newly_dead += 1;
+            }
+            let class = result.error_class.clone().unwrap_or_else(|| "unknown".to_string());
+            *report.by_error_class.entry(class).or_insert(0) += 1;
+            report.dead_links.push(DeadLink {
+                node_id: target.id,
+                url: target.url.clone(),
+                title: target.title.clone(),
+                status_code: result.status_code,
+                error_class: result.error_class.clone(),
+                error: result.error.clone(),
+                consecutive_failures: result.consecutive_failures,
+                is_alive: result.is_alive,
+                linked_from: linked_from(conn, target.id)?,
+            });
+        }
+
+        on_progress(LinkCheckProgress {
+            checked: report.checked,
+            total,
+            url: target.url.clone(),
+            result,
+        });
+    }
+    for worker in workers {
+        worker.join().map_err(|_| "Thread panic")?;
+    }
+
+    report.dead_links.sort_by_key(|d| (std::cmp::Reverse(d.consecutive_failures), d.node_id));
+    report.elapsed_ms = started.elapsed().as_millis() as u64;
+    Ok(report)
+}
+
+/// Checks whether every node's page is still up, `concurrency` hosts at a
+/// time (default 8, at most 32) and one request per host at a time. With
+/// `only_stale`, only nodes not crawled or checked in the last `stale_days`
+/// (default 7) are swept. Each result is emitted as a `link-check-progress`
+/// event; the returned report lists the links that failed. See
+/// `check_node_liveness` for `inspect_pages` and `failure_threshold`.
+#[tauri::command]
+pub async fn check_all_links(
+    app: tauri::AppHandle,
+    concurrency: Option<usize>,
+    only_stale: Option<bool>,
+    stale_days: Option<i64>,
+    inspect_pages: Option<bool>,
+    failure_threshold: Option<i64>,
+) -> Result<LinkCheckReport, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let concurrency = concurrency.unwrap_or(DEFAULT_CONCURRENCY).clamp(1, MAX_CONCURRENCY);
+    let stale_days = only_stale.unwrap_or(false).then(|| stale_days.unwrap_or(DEFAULT_STALE_DAYS));
+
+    std::thread::spawn(move || {
+        let conn = crate::open_void_db(&db_path)?;
+        check_all_links_in(
+            &conn,
+            concurrency,
+            stale_days,
+            inspect_pages.unwrap_or(false),
+            failure_threshold.unwrap_or(liveness::DEFAULT_FAILURE_THRESHOLD),
+            |progress| {
+                let _ = app.emit(LINK_CHECK_PROGRESS_EVENT, progress);
+            },
+        )
+    }).join().map_err(|_| "Thread panic")?
+}
@@ -0,0 +1,227 @@
+// ============== LIVENESS ==============
+//
+// A node is only marked dead after several fetches in a row have failed, so
+// one network blip does not kill it. Transient failures (timeouts, refused
+// connections, DNS hiccups, 5xx and 429) are retried with backoff first.
+// Pages that answer 200 but are really gone, like "page not found" pages and
+// parked domains, count as failures too (`soft_404` and `parked` classes).
+// Quick checks ask with HEAD and only fall back to GET when that fails, as
+// many servers mishandle HEAD.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use rusqlite::{Connection, params};
+use scraper::{Html, Selector};
+
+use crate::PageMetadata;
+use crate::crawl_log::{self, FetchError};
+use crate::redirects;
+
+/// Consecutive failed fetches before a node is marked dead.
+pub const DEFAULT_FAILURE_THRESHOLD: i64 = 3;
+/// Waits before each retry of a transient failure.
+const RETRY_DELAYS_MS: &[u64] = &[500, 2000];
+/// Parked and error pages are small; longer pages are left alone.
+const DEAD_PAGE_MAX_TEXT: usize = 2000;
+
+const SOFT_404_PHRASES: &[&str] = &[
+    "page not found", "not found", "error 404", "404 error", "page does not exist", "page doesn't exist",
+    "no longer available", "could not be found", "couldn't be found", "cannot be found", "can't be found",
+];
+
+/// Scripts and links of domain parking services.
+const PARKING_MARKERS: &[&str] = &[
+    "sedoparking.com", "parkingcrew.net", "bodis.com", "above.com/marketing", "//dan.com",
+    "afternic.com", "hugedomains.com", "parklogic", "domainmarket.com", "undeveloped.com",
+];
+
+const PARKED_PHRASES: &[&str] = &[
+    "domain is for sale", "buy this domain", "domain may be for sale", "domain has expired",
+    "this domain is parked", "parked free", "domain parking", "inquire about this domain",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LivenessResult {
+    pub node_id: i64,
+    /// Liveness as stored after this check
+    pub is_alive: bool,
+    /// Whether this check reached a live page
+    pub reachable: bool,
+    pub status_code: Option<u16>,
+    pub error_class: Option<String>,
+    pub error: Option<String>,
+    pub consecutive_failures: i64,
+    pub latency_ms: i64,
+}
+
+/// Why a page that loaded fine is still dead: `soft_404` for "not found"
+/// pages and deep links redirected to the home page, `parked` for parked or
+/// for-sale domains.
+pub(crate) fn dead_page_reason(requested_url: &str, final_url: &str, document: &Html, html: &str) -> Option<&'static str> {
+    let requested = url::Url::parse(requested_url).ok();
+    let landed = url::Url::parse(final_url).ok();
+    if let (Some(requested), Some(landed)) = (&requested, &landed) {
+        let same_site = requested.host_str().map(|h| h.trim_start_matches("www."))
+            == landed.host_str().map(|h| h.trim_start_matches("www."));
+        if same_site && !requested.path().trim_end_matches('/').is_empty() && landed.path() == "/" && landed.query().is_none() {
+            return Some("soft_404");
+        }
+    }
+
+    let text_length = crate::content::extract_readable_text(document).chars().count();
+    if text_length > DEAD_PAGE_MAX_TEXT {
+        return None;
+    }
+
+    let heading = |selector: &str| Selector::parse(selector).ok()
+        .and_then(|s| document.select(&s).next())
+        .map(|el| el.text().collect::<String>().to_lowercase())
+        .unwrap_or_default();
+    let title = heading("title");
+    let h1 = heading("h1");
+    let is_not_found = |text: &str| text.trim_start().starts_with("404") || SOFT_404_PHRASES.iter().any(|p| text.contains(p));
+    if is_not_found(&title) || is_not_found(&h1) {
+        return Some("soft_404");
+    }
+
+    let html = html.to_lowercase();
+    if PARKING_MARKERS.iter().any(|m| html.contains(m)) || PARKED_PHRASES.iter().any(|p| html.contains(p)) {
+        return Some("parked");
+    }
+    None
+}
+
+fn is_transient(result: &Result<PageMetadata, FetchError>) -> bool {
+    match result {
+        Ok(page) => matches!(page.status, Some(408 | 429 | 500..=599)),
+        Err(e) => matches!(e.class, "timeout" | "connect" | "dns" | "body"),
+    }
+}
+
+/// Runs `fetch`, retrying transient failures with backoff.
+pub(crate) fn with_retries(mut fetch: impl FnMut() -> Result<PageMetadata, FetchError>) -> Result<PageMetadata, FetchError> {
+    let mut result = fetch();
+    for &delay in RETRY_DELAYS_MS {
+        if !is_transient(&result) {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(delay));
+        result = fetch();
+    }
+    result
+}
+
+/// Whether `url` is up, asking with HEAD first. HEAD errors and non-success
+/// answers are retried with GET, as are HTML pages when `inspect_pages` is
+/// set so soft 404s and parked domains are caught. A HEAD-only answer has no
+/// title or links.
+pub(crate) fn check_url(url: &str, inspect_pages: bool) -> Result<PageMetadata, FetchError> {
+    let hops = Arc::new(Mutex::new(vec![]));
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+        .redirect(redirects::recording_policy(10, hops.clone()))
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    if let Ok(response) = client.head(url).send() {
+        let is_html = response.headers().get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("html"));
+        if response.status().is_success() && !(inspect_pages && is_html) {
+            return Ok(PageMetadata {
+                is_alive: true,
+                status: Some(response.status().as_u16()),
+                final_url: Some(response.url().to_string()),
+                redirect_chain: redirects::take_hops(&hops),
+                ..Default::default()
+            });
+        }
+    }
+    crate::fetch_page_metadata(url)
+}
+
+/// Failed fetches of `node_id` since its last successful one.
+pub(crate) fn consecutive_failures(conn: &Connection, node_id: i64) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT COUNT(*) FROM crawl_log
+         WHERE node_id = ?1
+           AND id > COALESCE((SELECT MAX(id) FROM crawl_log WHERE node_id = ?1 AND error_class IS NULL), 0)",
+        params![node_id],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())
+}
+
+/// Updates `is_alive` and `last_crawled` from the node's fetch log: alive
+/// after a success, dead once `threshold` fetches in a row have failed and
+/// unchanged in between. Returns the stored liveness and the failure count.
+pub(crate) fn update_liveness(conn: &Connection, node_id: i64, threshold: i64) -> Result<(bool, i64), String> {
+    let failures = consecutive_failures(conn, node_id)?;
+    conn.execute(
+        "UPDATE nodes SET
+            is_alive = CASE WHEN ?1 = 0 THEN 1 WHEN ?1 >= ?2 THEN 0 ELSE is_alive END,
+            last_crawled = datetime('now')
+         WHERE id = ?3",
+        params![failures, threshold.max(1), node_id],
+    ).map_err(|e| e.to_string())?;
+    let is_alive: i32 = conn.query_row("SELECT is_alive FROM nodes WHERE id = ?1", params![node_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    Ok((is_alive == 1, failures))
+}
+
+/// Logs a liveness check of `node_id` and updates its liveness.
+pub(crate) fn record_check(
+    conn: &Connection,
+    node_id: i64,
+    result: Result<PageMetadata, FetchError>,
+    latency_ms: u128,
+    threshold: i64,
+) -> Result<LivenessResult, String> {
+    crawl_log::log_fetch(conn, node_id, &result, latency_ms)?;
+    let (is_alive, failures) = update_liveness(conn, node_id, threshold)?;
+
+    let (reachable, status_code, error_class, error) = match result {
+        Ok(page) => (page.is_alive, page.status, crawl_log::page_class(&page).map(String::from), None),
+        Err(e) => (false, None, Some(e.class.to_string()), Some(e.message)),
+    };
+    Ok(LivenessResult {
+        node_id,
+        is_alive,
+        reachable,
+        status_code,
+        error_class,
+        error,
+        consecutive_failures: failures,
+        latency_ms: latency_ms as i64,
+    })
+}
+
+/// Checks whether a node's page is still up without refreshing its metadata.
+/// With `inspect_pages`, HTML pages are downloaded to catch soft 404s and
+/// parked domains. The node is marked dead after `failure_threshold`
+/// (default 3) failed checks in a row.
+#[tauri::command]
+pub async fn check_node_liveness(
+    app: tauri::AppHandle,
+    node_id: i64,
+    inspect_pages: Option<bool>,
+    failure_threshold: Option<i64>,
+) -> Result<LivenessResult, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+
+    std::thread::spawn(move || {
+        let conn = crate::open_void_db(&db_path)?;
+        let url: String = conn.query_row("SELECT url FROM nodes WHERE id = ?1", params![node_id], |row| row.get(0))
+            .map_err(|e| format!("Node not found: {}", e))?;
+        let started = std::time::Instant::now();
+        let result = with_retries(|| check_url(&url, inspect_pages.unwrap_or(false)));
+        record_check(
+            &conn,
+            node_id,
+            result,
+            started.elapsed().as_millis(),
+            failure_threshold.unwrap_or(DEFAULT_FAILURE_THRESHOLD),
+        )
+    }).join().map_err(|_| "Thread panic")?
+}
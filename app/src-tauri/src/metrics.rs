@@ -0,0 +1,380 @@
+// ============== GRAPH METRICS ==============
+//
+// Importance scores for every node, cached in `node_metrics` so the graph can
+// size nodes without recomputing: in/out degree, PageRank, HITS hub and
+// authority scores, and betweenness centrality estimated from a sample of
+// source nodes (Brandes).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use rusqlite::{Connection, params};
+
+use crate::layout;
+
+const PAGERANK_DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 100;
+const CONVERGENCE: f64 = 1e-8;
+const DEFAULT_TOP_N: usize = 10;
+const DEFAULT_BETWEENNESS_SAMPLES: usize = 256;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeScore {
+    pub node_id: i64,
+    pub url: String,
+    pub title: Option<String>,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeMetrics {
+    pub node_id: i64,
+    pub in_degree: i32,
+    pub out_degree: i32,
+    pub pagerank: f64,
+    pub hub: f64,
+    pub authority: f64,
+    pub betweenness: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphMetrics {
+    pub node_count: i32,
+    pub edge_count: i32,
+    pub computed_at: String,
+    /// Source nodes used for betweenness; equal to `node_count` when exact
+    pub betweenness_samples: i32,
+    pub top_pagerank: Vec<NodeScore>,
+    pub top_authorities: Vec<NodeScore>,
+    pub top_hubs: Vec<NodeScore>,
+    pub top_betweenness: Vec<NodeScore>,
+    pub top_in_degree: Vec<NodeScore>,
+    pub top_out_degree: Vec<NodeScore>,
+}
+
+/// Directed graph as adjacency lists, without duplicate edges or self-loops.
+pub(crate) struct Digraph {
+    pub outgoing: Vec<Vec<usize>>,
+    pub incoming: Vec<Vec<usize>>,
+}
+
+impl Digraph {
+    pub(crate) fn new(node_count: usize, edges: &[(usize, usize)]) -> Self {
+        let mut outgoing = vec![vec![]; node_count];
+        let mut incoming = vec![vec![]; node_count];
+        let mut seen = HashSet::new();
+        for &(a, b) in edges {
+            if seen.insert((a, b)) {
+                outgoing[a].push(b);
+                incoming[b].push(a);
+            }
+        }
+        Digraph { outgoing, incoming }
+    }
+
+    fn len(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    fn edge_count(&self) -> usize {
+        self.outgoing.iter().map(|o| o.len()).sum()
+    }
+
+    /// Scores sum to 1. Rank from dead ends is spread over every node.
+    fn pagerank(&self) -> Vec<f64> {
+        let n = self.len();
+        if n == 0 {
+            return vec![];
+        }
+        let base = (1.0 - PAGERANK_DAMPING) / n as f64;
+        let mut rank = vec![1.0 / n as f64; n];
+
+        for _ in 0..MAX_ITERATIONS {
+            let dangling: f64 = (0..n).filter(|&i| self.outgoing[i].is_empty()).map(|i| rank[i]).sum();
+            let mut next = vec![base + PAGERANK_DAMPING * dangling / n as f64; n];
+            for (i, targets) in self.outgoing.iter().enumerate() {
+                if targets.is_empty() {
+                    continue;
+                }
+                let share = PAGERANK_DAMPING * rank[i] / targets.len() as f64;
+                for &t in targets {
+                    next[t] += share;
+                }
+            }
+            let delta: f64 = rank.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+            rank = next;
+            if delta < CONVERGENCE {
+                break;
+            }
+        }
+        rank
+    }
+
+    /// Hub and authority scores, each scaled so the top node scores 1.
+    fn hits(&self) -> (Vec<f64>, Vec<f64>) {
+        let n = self.len();
+        let mut hubs = vec![1.0; n];
+        let mut authorities = vec![1.0; n];
+
+        for _ in 0..MAX_ITERATIONS {
+            let mut next_authorities: Vec<f64> = self.incoming.iter()
+                .map(|sources| sources.iter().map(|&s| hubs[s]).sum())
+                .collect();
+            normalize(&mut next_authorities);
+            let mut next_hubs: Vec<f64> = self.outgoing.iter()
+                .map(|targets| targets.iter().map(|&t| next_authorities[t]).sum())
+                .collect();
+            normalize(&mut next_hubs);
+
+            let delta: f64 = hubs.iter().zip(&next_hubs).map(|(a, b)| (a - b).abs()).sum::<f64>()
+                + authorities.iter().zip(&next_authorities).map(|(a, b)| (a - b).abs()).sum::<f64>();
+            hubs = next_hubs;
+            authorities = next_authorities;
+            if delta < CONVERGENCE {
+                break;
+            }
+        }
+        scale_to_max(&mut hubs);
+        scale_to_max(&mut authorities);
+        (hubs, authorities)
+    }
+
+    /// Brandes' algorithm from `samples` evenly spread source nodes, scaled up
+    /// to estimate the full value and normalized by (n-1)(n-2) to 0..1.
+    fn betweenness(&self, samples: usize) -> (Vec<f64>, usize) {
+        let n = self.len();
+        let mut centrality = vec![0.0; n];
+        if n < 3 {
+            return (centrality, n);
+        }
+        let samples = samples.clamp(1, n);
+        let sources = (0..samples).map(|k| k * n / samples);
+
+        let mut sigma = vec![0.0f64; n];
+        let mut distance = vec![-1i64; n];
+        let mut dependency = vec![0.0f64; n];
+        let mut predecessors: Vec<Vec<usize>> = vec![vec![]; n];
+        let mut order: Vec<usize> = Vec::with_capacity(n);
+        let mut queue = VecDeque::new();
+
+        for source in sources {
+            for i in order.drain(..) {
+                sigma[i] = 0.0;
+                distance[i] = -1;
+                dependency[i] = 0.0;
+                predecessors[i].clear();
+            }
+            sigma[source] = 1.0;
+            distance[source] = 0;
+            queue.push_back(source);
+
+            while let Some(v) = queue.pop_front() {
+                order.push(v);
+                for &w in &self.outgoing[v] {
+                    if distance[w] < 0 {
+                        distance[w] = distance[v] + 1;
+                        queue.push_back(w);
+                    }
+                    if distance[w] == distance[v] + 1 {
+                        sigma[w] += sigma[v];
+                        predecessors[w].push(v);
+                    }
+                }
+            }
+
+            for &w in order.iter().rev() {
+                for &v in &predecessors[w] {
+                    dependency[v] += sigma[v] / sigma[w] * (1.0 + dependency[w]);
+                }
+                if w != source {
+                    centrality[w] += dependency[w];
+                }
+            }
+        }
+
+        let scale = n as f64 / samples as f64 / ((n - 1) * (n - 2)) as f64;
+        for c in centrality.iter_mut() {
+            *c *= scale;
+        }
+        (centrality, samples)
+    }
+}
+
+fn normalize(values: &mut [f64]) {
+    let norm = values.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        for v in values.iter_mut() {
+            *v /= norm;
+        }
+    }
+}
+
+fn scale_to_max(values: &mut [f64]) {
+    let max = values.iter().cloned().fold(0.0, f64::max);
+    if max > 0.0 {
+        for v in values.iter_mut() {
+            *v /= max;
+        }
+    }
+}
+
+// ----- Cache -----
+
+fn top_nodes(conn: &Connection, column: &str, limit: usize) -> Result<Vec<NodeScore>, String> {
+    // `column` is always one of the fixed metric names below, never user input
+    let sql = format!(
+        "SELECT n.id, n.url, n.title, m.{0} FROM node_metrics m
+         JOIN nodes n ON n.id = m.node_id
+         WHERE m.{0} > 0
+         ORDER BY m.{0} DESC, n.id
+         LIMIT ?1",
+        column
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![limit as i64], |row| {
+        Ok(NodeScore {
+            node_id: row.get(0)?,
+            url: row.get(1)?,
+            title: row.get(2)?,
+            value: row.get(3)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+/// Reads the cached metrics, or `None` if they have never been computed.
+fn read_metrics(conn: &Connection, top_n: usize) -> Result<Option<GraphMetrics>, String> {
+    let summary = conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(out_degree), 0), MAX(computed_at), MAX(betweenness_samples) FROM node_metrics",
+        [],
+        |row| Ok((row.get::<_, i32>(0)?, row.get::<_, i32>(1)?, row.get::<_, Option<String>>(2)?, row.get::<_, Option<i32>>(3)?)),
+    ).map_err(|e| e.to_string())?;
+    let (node_count, edge_count, Some(computed_at), samples) = summary else {
+        return Ok(None);
+    };
+
+    Ok(Some(GraphMetrics {
+        node_count,
+        edge_count,
+        computed_at,
+        betweenness_samples: samples.unwrap_or(0),
+        top_pagerank: top_nodes(conn, "pagerank", top_n)?,
+        top_authorities: top_nodes(conn, "authority", top_n)?,
+        top_hubs: top_nodes(conn, "hub", top_n)?,
+        top_betweenness: top_nodes(conn, "betweenness", top_n)?,
+        top_in_degree: top_nodes(conn, "in_degree", top_n)?,
+        top_out_degree: top_nodes(conn, "out_degree", top_n)?,
+    }))
+}
+
+pub(crate) fn compute_graph_metrics_in(
+    conn: &mut Connection,
+    betweenness_samples: usize,
+    top_n: usize,
+) -> Result<GraphMetrics, String> {
+    let graph = layout::load_graph(conn)?;
+    let digraph = Digraph::new(graph.ids.len(), &graph.edges);
+
+    let pagerank = digraph.pagerank();
+    let (hubs, authorities) = digraph.hits();
+    let (betweenness, samples) = digraph.betweenness(betweenness_samples);
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM node_metrics", []).map_err(|e| e.to_string())?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO node_metrics
+             (node_id, in_degree, out_degree, pagerank, hub, authority, betweenness, betweenness_samples, computed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, datetime('now'))"
+        ).map_err(|e| e.to_string())?;
+        for (i, id) in graph.ids.iter().enumerate() {
+            stmt.execute(params![
+                id,
+                digraph.incoming[i].len() as i64,
+                digraph.outgoing[i].len() as i64,
+                pagerank[i],
+                hubs[i],
+                authorities[i],
+                betweenness[i],
+                samples as i64,
+            ]).map_err(|e| e.to_string())?;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    let metrics = read_metrics(conn, top_n)?;
+    Ok(metrics.unwrap_or_else(|| GraphMetrics {
+        node_count: 0,
+        edge_count: digraph.edge_count() as i32,
+        computed_at: String::new(),
+        betweenness_samples: 0,
+        top_pagerank: vec![],
+        top_authorities: vec![],
+        top_hubs: vec![],
+        top_betweenness: vec![],
+        top_in_degree: vec![],
+        top_out_degree: vec![],
+    }))
+}
+
+/// Computes degree, PageRank, HITS and betweenness for every node in the
+/// active void and caches them. Betweenness is estimated from
+/// `betweenness_samples` source nodes (default 256); pass a value at least
+/// the node count for the exact result. Returns the `top_n` nodes per metric.
+#[tauri::command]
+pub async fn compute_graph_metrics(
+    app: tauri::AppHandle,
+    top_n: Option<usize>,
+    betweenness_samples: Option<usize>,
+) -> Result<GraphMetrics, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+
+    std::thread::spawn(move || {
+        let mut conn = crate::open_void_db(&db_path)?;
+        compute_graph_metrics_in(
+            &mut conn,
+            betweenness_samples.unwrap_or(DEFAULT_BETWEENNESS_SAMPLES),
+            top_n.unwrap_or(DEFAULT_TOP_N),
+        )
+    }).join().map_err(|_| "Thread panic")?
+}
+
+/// Returns the cached metrics from the last `compute_graph_metrics` run.
+/// `top_n` lists only; use `get_node_metrics` for per-node values.
+#[tauri::command]
+pub async fn get_graph_metrics(app: tauri::AppHandle, top_n: Option<usize>) -> Result<Option<GraphMetrics>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+    read_metrics(&conn, top_n.unwrap_or(DEFAULT_TOP_N))
+}
+
+/// Cached metrics for `node_ids`, or for every node when omitted, so node
+/// size can follow any of the scores.
+#[tauri::command]
+pub async fn get_node_metrics(app: tauri::AppHandle, node_ids: Option<Vec<i64>>) -> Result<Vec<NodeMetrics>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+    let wanted: Option<HashSet<i64>> = node_ids.map(|ids| ids.into_iter().collect());
+
+    let mut stmt = conn.prepare(
+        "SELECT node_id, in_degree, out_degree, pagerank, hub, authority, betweenness
+         FROM node_metrics ORDER BY node_id"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| {
+        Ok(NodeMetrics {
+            node_id: row.get(0)?,
+            in_degree: row.get(1)?,
+            out_degree: row.get(2)?,
+            pagerank: row.get(3)?,
+            hub: row.get(4)?,
+            authority: row.get(5)?,
+            betweenness: row.get(6)?,
+        })
+    }).map_err(|e| e.to_string())?;
+
+    Ok(rows.flatten()
+        .filter(|m| match &wanted {
+            Some(ids) => ids.contains(&m.node_id),
+            None => true,
+        })
+        .collect())
+}
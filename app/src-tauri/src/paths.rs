@@ -0,0 +1,220 @@
+// ============== PATHS & NEIGHBORHOODS ==============
+//
+// Route finding between nodes, k-hop subgraphs around a node and connected
+// components, so the UI can highlight a route and fly the camera along it.
+// Edges are unweighted, so breadth-first search gives the shortest path.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::hash_map::Entry;
+use rusqlite::{Connection, params};
+
+use crate::{VoidEdge, VoidNode, VOID_NODE_COLUMNS, void_node_from_row};
+use crate::layout;
+use crate::metrics::Digraph;
+
+const DEFAULT_NEIGHBORHOOD_HOPS: u32 = 1;
+const MAX_NEIGHBORHOOD_HOPS: u32 = 6;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphPath {
+    /// Source first, target last
+    pub nodes: Vec<VoidNode>,
+    pub hops: i32,
+    pub directed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeighborNode {
+    pub node: VoidNode,
+    /// Hops from the center node
+    pub distance: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Neighborhood {
+    pub center_id: i64,
+    pub hops: u32,
+    /// Closest first, starting with the center node itself
+    pub nodes: Vec<NeighborNode>,
+    /// Edges between nodes of the neighborhood
+    pub edges: Vec<VoidEdge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentInfo {
+    pub id: i32,
+    pub size: i32,
+    pub node_ids: Vec<i64>,
+}
+
+/// The link graph with node ids, for searches that follow edges both ways.
+struct IndexedGraph {
+    ids: Vec<i64>,
+    index: HashMap<i64, usize>,
+    digraph: Digraph,
+}
+
+impl IndexedGraph {
+    fn load(conn: &Connection) -> Result<Self, String> {
+        let graph = layout::load_graph(conn)?;
+        let index = graph.ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        let digraph = Digraph::new(graph.ids.len(), &graph.edges);
+        Ok(IndexedGraph { ids: graph.ids, index, digraph })
+    }
+
+    fn position(&self, id: i64) -> Result<usize, String> {
+        self.index.get(&id).copied().ok_or_else(|| format!("Node {} not found", id))
+    }
+
+    fn neighbors(&self, i: usize, directed: bool) -> impl Iterator<Item = usize> + '_ {
+        let incoming: &[usize] = if directed { &[] } else { &self.digraph.incoming[i] };
+        self.digraph.outgoing[i].iter().chain(incoming).copied()
+    }
+
+    /// Breadth-first search from `start`, stopping once `target` is reached or
+    /// `max_hops` is exceeded. Returns hop distance and BFS parent per reached node.
+    fn bfs(
+        &self,
+        start: usize,
+        target: Option<usize>,
+        max_hops: u32,
+        directed: bool,
+    ) -> (HashMap<usize, u32>, HashMap<usize, usize>) {
+        let mut distance = HashMap::from([(start, 0)]);
+        let mut parent = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(v) = queue.pop_front() {
+            if Some(v) == target {
+                break;
+            }
+            let d = distance[&v];
+            if d >= max_hops {
+                continue;
+            }
+            for w in self.neighbors(v, directed) {
+                if let Entry::Vacant(entry) = distance.entry(w) {
+                    entry.insert(d + 1);
+                    parent.insert(w, v);
+                    queue.push_back(w);
+                }
+            }
+        }
+        (distance, parent)
+    }
+}
+
+fn fetch_nodes(conn: &Connection, ids: &[i64]) -> Result<Vec<VoidNode>, String> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM nodes WHERE id = ?1", VOID_NODE_COLUMNS))
+        .map_err(|e| e.to_string())?;
+    ids.iter()
+        .map(|id| stmt.query_row(params![id], void_node_from_row).map_err(|e| e.to_string()))
+        .collect()
+}
+
+pub(crate) fn find_path_in(
+    conn: &Connection,
+    source_id: i64,
+    target_id: i64,
+    directed: bool,
+) -> Result<Option<GraphPath>, String> {
+    let graph = IndexedGraph::load(conn)?;
+    let source = graph.position(source_id)?;
+    let target = graph.position(target_id)?;
+
+    let (_, parent) = graph.bfs(source, Some(target), u32::MAX, directed);
+    if source != target && !parent.contains_key(&target) {
+        return Ok(None);
+    }
+
+    let mut route = vec![graph.ids[target]];
+    let mut current = target;
+    while let Some(&previous) = parent.get(&current) {
+        route.push(graph.ids[previous]);
+        current = previous;
+    }
+    route.reverse();
+
+    Ok(Some(GraphPath {
+        hops: route.len() as i32 - 1,
+        nodes: fetch_nodes(conn, &route)?,
+        directed,
+    }))
+}
+
+pub(crate) fn neighborhood_in(conn: &Connection, node_id: i64, hops: u32) -> Result<Neighborhood, String> {
+    let graph = IndexedGraph::load(conn)?;
+    let center = graph.position(node_id)?;
+    let (distance, _) = graph.bfs(center, None, hops, false);
+
+    let mut members: Vec<(usize, u32)> = distance.into_iter().collect();
+    members.sort_by_key(|&(i, d)| (d, graph.ids[i]));
+    let ids: Vec<i64> = members.iter().map(|&(i, _)| graph.ids[i]).collect();
+    let nodes = fetch_nodes(conn, &ids)?
+        .into_iter()
+        .zip(&members)
+        .map(|(node, &(_, distance))| NeighborNode { node, distance })
+        .collect();
+
+    let inside: HashSet<i64> = ids.iter().copied().collect();
+    let mut stmt = conn.prepare("SELECT id, source_id, target_id, edge_type FROM edges ORDER BY id")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| {
+        Ok(VoidEdge { id: row.get(0)?, source_id: row.get(1)?, target_id: row.get(2)?, edge_type: row.get(3)? })
+    }).map_err(|e| e.to_string())?;
+    let edges = rows.flatten()
+        .filter(|e| inside.contains(&e.source_id) && inside.contains(&e.target_id))
+        .collect();
+
+    Ok(Neighborhood { center_id: node_id, hops, nodes, edges })
+}
+
+/// Shortest route from `source_id` to `target_id`, following links in their
+/// direction when `directed` (default false), or `None` if they are not
+/// connected.
+#[tauri::command]
+pub async fn find_path(
+    app: tauri::AppHandle,
+    source_id: i64,
+    target_id: i64,
+    directed: Option<bool>,
+) -> Result<Option<GraphPath>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+
+    std::thread::spawn(move || {
+        let conn = crate::open_void_db(&db_path)?;
+        find_path_in(&conn, source_id, target_id, directed.unwrap_or(false))
+    }).join().map_err(|_| "Thread panic")?
+}
+
+/// Every node within `hops` links of `node_id` (default 1, at most 6), in
+/// either direction, plus the edges between them.
+#[tauri::command]
+pub async fn get_neighborhood(app: tauri::AppHandle, node_id: i64, hops: Option<u32>) -> Result<Neighborhood, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let hops = hops.unwrap_or(DEFAULT_NEIGHBORHOOD_HOPS).min(MAX_NEIGHBORHOOD_HOPS);
+
+    std::thread::spawn(move || {
+        let conn = crate::open_void_db(&db_path)?;
+        neighborhood_in(&conn, node_id, hops)
+    }).join().map_err(|_| "Thread panic")?
+}
+
+/// Groups of nodes reachable from each other, largest first. Components
+/// smaller than `min_size` (default 1) are left out.
+#[tauri::command]
+pub async fn connected_components(app: tauri::AppHandle, min_size: Option<usize>) -> Result<Vec<ComponentInfo>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let min_size = min_size.unwrap_or(1);
+
+    std::thread::spawn(move || {
+        let conn = crate::open_void_db(&db_path)?;
+        let components = crate::stats::connected_components_in(&conn)?;
+        Ok(components.into_iter()
+            .filter(|c| c.len() >= min_size)
+            .enumerate()
+            .map(|(id, node_ids)| ComponentInfo { id: id as i32, size: node_ids.len() as i32, node_ids })
+            .collect())
+    }).join().map_err(|_| "Thread panic")?
+}
@@ -0,0 +1,205 @@
+// ============== REDIRECTS ==============
+//
+// A crawl that lands on a different URL than the node's own makes the node
+// an alias: `canonical_node_id` points at the node for the URL the redirects
+// ended on (created if the void has none) and a `redirects` edge joins the
+// two. With auto-merge the alias is folded into that node instead, its edges
+// re-pointed, so `http://x` and `https://www.x/` end up as one node.
+
+use std::sync::{Arc, Mutex};
+use reqwest::redirect::Policy;
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::{PageMetadata, VoidNode, VOID_NODE_COLUMNS, void_node_from_row};
+
+pub const LINK_EDGE_TYPE: &str = "link";
+pub const REDIRECT_EDGE_TYPE: &str = "redirects";
+
+/// Follows up to `max` redirects like `Policy::limited`, keeping the URLs
+/// redirected from in `hops`.
+pub(crate) fn recording_policy(max: usize, hops: Arc<Mutex<Vec<String>>>) -> Policy {
+    Policy::custom(move |attempt| {
+        if attempt.previous().len() > max {
+            return attempt.error("too many redirects");
+        }
+        if let Ok(mut hops) = hops.lock() {
+            *hops = attempt.previous().iter().map(|u| u.to_string()).collect();
+        }
+        attempt.follow()
+    })
+}
+
+pub(crate) fn take_hops(hops: &Mutex<Vec<String>>) -> Vec<String> {
+    hops.lock().map(|mut h| std::mem::take(&mut *h)).unwrap_or_default()
+}
+
+/// Where a crawled page ended up in the void.
+pub(crate) struct RedirectResolution {
+    /// Node that holds the page: the crawled node unless it redirected
+    pub page_node_id: i64,
+    /// The crawled node was merged into `page_node_id` and no longer exists
+    pub merged: bool,
+}
+
+/// Moves every edge of `remove_id` onto `keep_id`, then deletes `remove_id`.
+/// Edges that would become self-loops or duplicates are dropped and aliases
+/// of the removed node become aliases of the kept one. Its fetch log and
+/// crawl history move over, as do its saved content versions if the kept
+/// node has none; its other per-node rows are deleted with it.
+pub(crate) fn merge_node_into(conn: &Connection, remove_id: i64, keep_id: i64) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR IGNORE INTO edges (source_id, target_id, edge_type)
+         SELECT source, target, edge_type FROM (
+             SELECT CASE source_id WHEN ?1 THEN ?2 ELSE source_id END AS source,
+                    CASE target_id WHEN ?1 THEN ?2 ELSE target_id END AS target,
+                    edge_type, id
+             FROM edges WHERE source_id = ?1 OR target_id = ?1
+         )
+         WHERE source != target
+         ORDER BY id",
+        params![remove_id, keep_id],
+    ).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM edges WHERE source_id = ?1 OR target_id = ?1", params![remove_id])
+        .map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE nodes SET canonical_node_id = CASE id WHEN ?2 THEN NULL ELSE ?2 END WHERE canonical_node_id = ?1",
+        params![remove_id, keep_id],
+    ).map_err(|e| e.to_string())?;
+    for table in ["crawl_log", "crawl_history"] {
+        conn.execute(&format!("UPDATE {} SET node_id = ?2 WHERE node_id = ?1", table), params![remove_id, keep_id])
+            .map_err(|e| e.to_string())?;
+    }
+    conn.execute(
+        "UPDATE node_content SET node_id = ?2
+         WHERE node_id = ?1 AND NOT EXISTS (SELECT 1 FROM node_content WHERE node_id = ?2)",
+        params![remove_id, keep_id],
+    ).map_err(|e| e.to_string())?;
+    // Foreign keys are not enforced on these connections, so nothing cascades
+    for table in ["node_content", "node_metrics", "layout_positions"] {
+        conn.execute(&format!("DELETE FROM {} WHERE node_id = ?1", table), params![remove_id])
+            .map_err(|e| e.to_string())?;
+    }
+    conn.execute("DELETE FROM nodes WHERE id = ?1", params![remove_id])
+        .map_err(|e| format!("Failed to remove node {}: {}", remove_id, e))?;
+    Ok(())
+}
+
+/// Marks `alias_id` as redirecting to `canonical_id`, replacing any
+/// `redirects` edge it had to another node.
+fn link_alias(conn: &Connection, alias_id: i64, canonical_id: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE nodes SET canonical_node_id = ?1 WHERE id = ?2",
+        params![canonical_id, alias_id],
+    ).map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM edges WHERE source_id = ?1 AND edge_type = ?2 AND target_id != ?3",
+        params![alias_id, REDIRECT_EDGE_TYPE, canonical_id],
+    ).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO edges (source_id, target_id, edge_type) VALUES (?1, ?2, ?3)
+         ON CONFLICT(source_id, target_id) DO UPDATE SET edge_type = excluded.edge_type",
+        params![alias_id, canonical_id, REDIRECT_EDGE_TYPE],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Adds a node for `url` near `near_id`, the way discovery adds new links.
+fn insert_canonical_node(conn: &Connection, near_id: i64, url: &str) -> Result<i64, String> {
+    let (x, y, z): (f64, f64, f64) = conn.query_row(
+        "SELECT position_x, position_y, position_z FROM nodes WHERE id = ?1",
+        params![near_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    ).map_err(|e| e.to_string())?;
+    let (x, y, z) = crate::generate_nearby_position(x, y, z);
+    let domain = url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    conn.execute(
+        "INSERT INTO nodes (url, title, position_x, position_y, position_z, is_alive, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, 1, datetime('now'))",
+        params![url, domain, x, y, z],
+    ).map_err(|e| e.to_string())?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Records where a crawl of `node_id` was redirected to. Without redirects,
+/// or when they end on the node's own URL, the node stops being an alias.
+/// Otherwise it becomes an alias of the node for the final URL or, with
+/// `merge`, is merged into it (or takes over the final URL if there is no
+/// such node). Failed fetches, error pages and soft 404s leave the node as it
+/// is, so a dead link redirected to a home page is not tied to that page.
+pub(crate) fn resolve_redirect(
+    conn: &mut Connection,
+    node_id: i64,
+    page: &PageMetadata,
+    merge: bool,
+) -> Result<RedirectResolution, String> {
+    let unchanged = RedirectResolution { page_node_id: node_id, merged: false };
+    if !page.is_alive {
+        return Ok(unchanged);
+    }
+    let node_url: String = conn.query_row("SELECT url FROM nodes WHERE id = ?1", params![node_id], |row| row.get(0))
+        .map_err(|e| format!("Node not found: {}", e))?;
+
+    let final_url = page.final_url.as_deref()
+        .filter(|_| !page.redirect_chain.is_empty())
+        .and_then(crate::clean_link)
+        .filter(|url| *url != node_url.trim_end_matches('/'));
+    let Some(final_url) = final_url else {
+        conn.execute("UPDATE nodes SET canonical_node_id = NULL WHERE id = ?1", params![node_id])
+            .map_err(|e| e.to_string())?;
+        conn.execute(
+            "DELETE FROM edges WHERE source_id = ?1 AND edge_type = ?2",
+            params![node_id, REDIRECT_EDGE_TYPE],
+        ).map_err(|e| e.to_string())?;
+        return Ok(unchanged);
+    };
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    // An existing node for the final URL may itself be an alias
+    let existing: Option<i64> = tx.query_row(
+        "SELECT COALESCE(canonical_node_id, id) FROM nodes WHERE url = ?1",
+        params![final_url],
+        |row| row.get(0),
+    ).optional().map_err(|e| e.to_string())?;
+
+    let resolution = match existing {
+        Some(target_id) if target_id == node_id => unchanged,
+        Some(target_id) if merge => {
+            merge_node_into(&tx, node_id, target_id)?;
+            RedirectResolution { page_node_id: target_id, merged: true }
+        }
+        Some(target_id) => {
+            link_alias(&tx, node_id, target_id)?;
+            RedirectResolution { page_node_id: target_id, merged: false }
+        }
+        None if merge => {
+            tx.execute("UPDATE nodes SET url = ?1, canonical_node_id = NULL WHERE id = ?2", params![final_url, node_id])
+                .map_err(|e| e.to_string())?;
+            unchanged
+        }
+        None => {
+            let target_id = insert_canonical_node(&tx, node_id, &final_url)?;
+            link_alias(&tx, node_id, target_id)?;
+            RedirectResolution { page_node_id: target_id, merged: false }
+        }
+    };
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(resolution)
+}
+
+/// Nodes whose URL redirects to `node_id`.
+#[tauri::command]
+pub async fn get_redirect_aliases(app: tauri::AppHandle, node_id: i64) -> Result<Vec<VoidNode>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM nodes WHERE canonical_node_id = ?1 ORDER BY id",
+        VOID_NODE_COLUMNS
+    )).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![node_id], void_node_from_row).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
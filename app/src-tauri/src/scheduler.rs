@@ -0,0 +1,186 @@
+// ============== CRAWL SCHEDULER ==============
+//
+// Picks what to crawl next. Every node due for a crawl gets a score from how
+// overdue it is, how important it is in the graph (PageRank and degree from
+// `node_metrics`, degree alone if metrics were never computed), whether the
+// user pinned it and how often its content changed on past crawls. Nodes
+// whose recent fetches failed back off exponentially. Batches go round-robin
+// over domains so one big site cannot fill them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use rusqlite::{Connection, params};
+
+use crate::{VoidNode, VOID_NODE_COLUMNS, void_node_from_row};
+
+const DEFAULT_BATCH_SIZE: usize = 10;
+const MAX_BATCH_SIZE: usize = 500;
+const DEFAULT_STALE_DAYS: i64 = 7;
+/// Failed fetches in a row double the wait before the next try, up to this many times.
+const MAX_BACKOFF_DOUBLINGS: u32 = 4;
+
+const STALENESS_WEIGHT: f64 = 1.0;
+const IMPORTANCE_WEIGHT: f64 = 1.0;
+const PINNED_WEIGHT: f64 = 0.75;
+const CHANGE_WEIGHT: f64 = 0.75;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlTarget {
+    pub node: VoidNode,
+    pub score: f64,
+    /// 1 for never crawled, approaching 1 the longer a crawl is overdue
+    pub staleness: f64,
+    /// PageRank and degree relative to the best candidate, 0..1
+    pub importance: f64,
+    /// Smoothed share of past crawls that found changed content
+    pub change_rate: f64,
+    pub consecutive_failures: i64,
+}
+
+struct Candidate {
+    node: VoidNode,
+    age_days: Option<f64>,
+    pagerank: f64,
+    degree: i64,
+    crawls: i64,
+    changes: i64,
+}
+
+fn domain_of(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_lowercase()))
+        .unwrap_or_default()
+}
+
+/// Failed fetches since the last successful one, for nodes with any.
+fn failure_counts(conn: &Connection) -> Result<HashMap<i64, i64>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT node_id, COUNT(*) FROM crawl_log c
+         WHERE id > COALESCE((SELECT MAX(id) FROM crawl_log WHERE node_id = c.node_id AND error_class IS NULL), 0)
+         GROUP BY node_id"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+/// Web nodes not crawled within `stale_days`. Aliases are left to their
+/// canonical node.
+fn load_candidates(conn: &Connection, stale_days: i64) -> Result<Vec<Candidate>, String> {
+    let columns = crate::void_node_columns_as("n");
+    let extra = VOID_NODE_COLUMNS.split(", ").count();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {},
+                julianday('now') - julianday(n.last_crawled),
+                COALESCE(m.pagerank, 0),
+                COALESCE(m.in_degree + m.out_degree, d.degree, 0),
+                (SELECT COUNT(*) FROM crawl_history WHERE node_id = n.id),
+                (SELECT COUNT(*) FROM crawl_history WHERE node_id = n.id AND changed = 1)
+         FROM nodes n
+         LEFT JOIN node_metrics m ON m.node_id = n.id
+         LEFT JOIN (
+             SELECT id, COUNT(*) AS degree FROM (
+                 SELECT source_id AS id FROM edges UNION ALL SELECT target_id FROM edges
+             ) GROUP BY id
+         ) d ON d.id = n.id
+         WHERE n.url LIKE 'http%'
+           AND n.canonical_node_id IS NULL
+           AND (n.last_crawled IS NULL OR n.last_crawled < datetime('now', ?1))",
+        columns
+    )).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![format!("-{} days", stale_days)], |row| {
+        Ok(Candidate {
+            node: void_node_from_row(row)?,
+            age_days: row.get(extra)?,
+            pagerank: row.get(extra + 1)?,
+            degree: row.get(extra + 2)?,
+            crawls: row.get(extra + 3)?,
+            changes: row.get(extra + 4)?,
+        })
+    }).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+/// Scores every due node and returns up to `batch_size` of them, the best of
+/// each domain before the second best of any, and at most
+/// `per_domain_limit` per domain when given.
+pub(crate) fn crawl_batch_in(
+    conn: &Connection,
+    batch_size: usize,
+    stale_days: i64,
+    per_domain_limit: Option<usize>,
+) -> Result<Vec<CrawlTarget>, String> {
+    let stale_days = stale_days.max(0);
+    let failures = failure_counts(conn)?;
+    let candidates = load_candidates(conn, stale_days)?;
+
+    let max_pagerank = candidates.iter().map(|c| c.pagerank).fold(0.0, f64::max);
+    let max_degree = candidates.iter().map(|c| c.degree).max().unwrap_or(0);
+
+    let mut scored: Vec<(String, CrawlTarget)> = candidates.into_iter().filter_map(|c| {
+        let consecutive_failures = failures.get(&c.node.id).copied().unwrap_or(0);
+        let backoff = 2f64.powi(consecutive_failures.min(MAX_BACKOFF_DOUBLINGS as i64) as i32);
+
+        let staleness = match c.age_days {
+            None => 1.0,
+            Some(age) => {
+                // Failing nodes are not due again until their backoff has passed
+                let due_after = stale_days as f64 * backoff;
+                if consecutive_failures > 0 && age < due_after {
+                    return None;
+                }
+                1.0 - 1.0 / (age / (stale_days as f64).max(1.0)).max(1.0)
+            }
+        };
+        let pagerank = if max_pagerank > 0.0 { c.pagerank / max_pagerank } else { 0.0 };
+        let degree = if max_degree > 0 { (c.degree as f64).ln_1p() / (max_degree as f64).ln_1p() } else { 0.0 };
+        let importance = if max_pagerank > 0.0 { (pagerank + degree) / 2.0 } else { degree };
+        let change_rate = (c.changes as f64 + 1.0) / (c.crawls as f64 + 2.0);
+        let pinned = if c.node.pinned { 1.0 } else { 0.0 };
+
+        let score = (STALENESS_WEIGHT * staleness
+            + IMPORTANCE_WEIGHT * importance
+            + PINNED_WEIGHT * pinned
+            + CHANGE_WEIGHT * change_rate)
+            / backoff;
+        Some((domain_of(&c.node.url), CrawlTarget {
+            node: c.node,
+            score,
+            staleness,
+            importance,
+            change_rate,
+            consecutive_failures,
+        }))
+    }).collect();
+    scored.sort_by(|a, b| b.1.score.total_cmp(&a.1.score).then(a.1.node.id.cmp(&b.1.node.id)));
+
+    // Rank within each domain, then take the best of every domain, the second best, ...
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut ranked: Vec<(usize, CrawlTarget)> = scored.into_iter().filter_map(|(domain, target)| {
+        let rank = seen.entry(domain).or_insert(0);
+        *rank += 1;
+        (!matches!(per_domain_limit, Some(limit) if *rank > limit)).then_some((*rank, target))
+    }).collect();
+    ranked.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.score.total_cmp(&a.1.score)));
+
+    Ok(ranked.into_iter().take(batch_size).map(|(_, target)| target).collect())
+}
+
+/// The next `batch_size` nodes to crawl (default 10), highest priority first
+/// with domains taking turns. Nodes are due once they have not been crawled
+/// for `stale_days` (default 7), longer after failed fetches.
+#[tauri::command]
+pub async fn get_crawl_batch(
+    app: tauri::AppHandle,
+    batch_size: Option<usize>,
+    stale_days: Option<i64>,
+    per_domain_limit: Option<usize>,
+) -> Result<Vec<CrawlTarget>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let batch_size = batch_size.unwrap_or(DEFAULT_BATCH_SIZE).clamp(1, MAX_BATCH_SIZE);
+
+    std::thread::spawn(move || {
+        let conn = crate::open_void_db(&db_path)?;
+        crawl_batch_in(&conn, batch_size, stale_days.unwrap_or(DEFAULT_STALE_DAYS), per_domain_limit)
+    }).join().map_err(|_| "Thread panic")?
+}
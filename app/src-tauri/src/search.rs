@@ -0,0 +1,162 @@
+// ============== SEARCH ==============
+//
+// Full-text search over the `node_search` FTS5 index, which triggers on
+// `nodes` keep in step with each node's URL, title and description. The
+// `body` column holds extracted page text when there is any.
+
+use serde::{Deserialize, Serialize};
+use rusqlite::types::Value;
+use rusqlite::{Connection, params_from_iter};
+
+use crate::{VoidNode, VOID_NODE_COLUMNS, void_node_from_row};
+
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+const MAX_SEARCH_LIMIT: usize = 500;
+/// Column weights for bm25: url, title, description, body.
+const RANK_WEIGHTS: &str = "3.0, 10.0, 5.0, 1.0";
+const SNIPPET_TOKENS: i32 = 16;
+/// highlight()/snippet() wrap matches in these control characters, which
+/// `mark_matches` turns into `<mark>` tags once the text is escaped.
+const MATCH_START: char = '\u{2}';
+const MATCH_END: char = '\u{3}';
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchFilters {
+    /// Only nodes on these sites (subdomains included)
+    #[serde(default)]
+    pub domains: Vec<String>,
+    /// Only live (`true`) or dead (`false`) nodes
+    #[serde(default)]
+    pub alive: Option<bool>,
+    #[serde(default)]
+    pub cluster_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub node: VoidNode,
+    /// Lower is a better match
+    pub rank: f64,
+    /// HTML-escaped title with matches wrapped in `<mark>`
+    pub title_highlight: String,
+    /// HTML-escaped best matching passage with matches wrapped in `<mark>`
+    pub snippet: String,
+}
+
+/// Turns free text into an FTS5 query where every word must match, as a
+/// prefix of a token, in any column. Quoting each word keeps FTS5 operators
+/// and punctuation in user input from being interpreted.
+fn build_match_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| c.is_whitespace() || c == '"')
+        .filter(|w| w.chars().any(|c| c.is_alphanumeric()))
+        .map(|w| format!("\"{}\"*", w))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Escapes crawled text for HTML, then turns the match markers into
+/// `<mark>` tags.
+fn mark_matches(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            MATCH_START => out.push_str("<mark>"),
+            MATCH_END => out.push_str("</mark>"),
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+pub(crate) fn search_nodes_in(
+    conn: &Connection,
+    query: &str,
+    filters: &SearchFilters,
+    limit: usize,
+) -> Result<Vec<SearchHit>, String> {
+    let Some(match_query) = build_match_query(query) else {
+        return Ok(vec![]);
+    };
+
+    // node_search has url/title/description columns too, so qualify the node's
+    let node_columns = crate::void_node_columns_as("n");
+    let extra = VOID_NODE_COLUMNS.split(", ").count();
+    let mut sql = format!(
+        "SELECT {columns},
+                bm25(node_search, {weights}) AS rank,
+                highlight(node_search, 1, char(2), char(3)),
+                snippet(node_search, -1, char(2), char(3), '…', {tokens})
+         FROM node_search
+         JOIN nodes n ON n.id = node_search.rowid
+         WHERE node_search MATCH ?",
+        columns = node_columns,
+        weights = RANK_WEIGHTS,
+        tokens = SNIPPET_TOKENS,
+    );
+    let mut values: Vec<Value> = vec![Value::Text(match_query)];
+
+    if let Some(alive) = filters.alive {
+        sql.push_str(" AND n.is_alive = ?");
+        values.push(Value::Integer(if alive { 1 } else { 0 }));
+    }
+    if let Some(cluster_id) = filters.cluster_id {
+        sql.push_str(" AND n.cluster_id = ?");
+        values.push(Value::Integer(cluster_id));
+    }
+
+    let domains: Vec<String> = filters.domains.iter()
+        .map(|d| d.trim().trim_start_matches("*.").trim_start_matches('.').to_lowercase())
+        .filter(|d| !d.is_empty())
+        .collect();
+    if !domains.is_empty() {
+        // The host is followed by a path, port, query or nothing at all
+        let mut clauses = vec![];
+        for domain in domains {
+            for host in [format!("://{}", domain), format!(".{}", domain)] {
+                clauses.push("n.url LIKE ? OR n.url LIKE ? OR n.url LIKE ? OR n.url LIKE ?");
+                values.push(Value::Text(format!("%{}", host)));
+                for end in ['/', ':', '?'] {
+                    values.push(Value::Text(format!("%{}{}%", host, end)));
+                }
+            }
+        }
+        sql.push_str(&format!(" AND ({})", clauses.join(" OR ")));
+    }
+
+    sql.push_str(" ORDER BY rank LIMIT ?");
+    values.push(Value::Integer(limit as i64));
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params_from_iter(values), |row| {
+        Ok(SearchHit {
+            node: void_node_from_row(row)?,
+            rank: row.get(extra)?,
+            title_highlight: mark_matches(&row.get::<_, String>(extra + 1)?),
+            snippet: mark_matches(&row.get::<_, String>(extra + 2)?),
+        })
+    }).map_err(|e| format!("Search failed: {}", e))?;
+
+    Ok(rows.flatten().collect())
+}
+
+/// Searches URLs, titles, descriptions and page text in the active void.
+/// Every word of `query` must match the start of a word somewhere in the
+/// node; title matches rank highest. At most `limit` hits (default 50).
+#[tauri::command]
+pub async fn search_nodes(
+    app: tauri::AppHandle,
+    query: String,
+    filters: Option<SearchFilters>,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+    let conn = crate::open_void_db(&db_path)?;
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+    search_nodes_in(&conn, &query, &filters.unwrap_or_default(), limit)
+}
@@ -0,0 +1,393 @@
+// ============== SITEMAP & FEED DISCOVERY ==============
+//
+// Extra link sources beyond a page's `<a href>`s: the sitemaps a site lists in
+// robots.txt (or the conventional /sitemap.xml), including sitemap indexes and
+// gzipped sitemaps, and the items of RSS/Atom feeds a page advertises with
+// `<link rel="alternate">`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Read;
+use flate2::read::GzDecoder;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use rand::Rng;
+use rusqlite::params;
+use scraper::{Html, Selector};
+
+use crate::graph_import::{local_name, xml_attributes, XmlText};
+
+/// Sitemap pages mixed into a single `discover_links_from_node` call.
+pub(crate) const DISCOVERY_SITEMAP_LIMIT: usize = 500;
+/// Sitemap pages gathered by `seed_from_sitemap` before `max_pages` is applied.
+const SEED_SITEMAP_LIMIT: usize = 5000;
+/// Sitemap files (indexes included) read per site.
+const MAX_SITEMAP_FILES: usize = 50;
+/// The sitemap protocol caps a single (uncompressed) file at 50MB.
+const MAX_SITEMAP_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SitemapSeedResult {
+    pub root_node_id: i64,
+    pub sitemaps_read: i32,
+    pub urls_found: i32,
+    pub nodes_added: i32,
+    pub edges_added: i32,
+    pub new_node_ids: Vec<i64>,
+    /// Pages the session's discovery rules kept out
+    pub urls_filtered: i32,
+}
+
+fn http_client() -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Fetches a URL, transparently gunzipping `.xml.gz` sitemaps. Both the
+/// download and the unzipped text stop at `MAX_SITEMAP_BYTES`.
+fn fetch_text(client: &reqwest::blocking::Client, url: &str) -> Option<String> {
+    let response = client.get(url).send().ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let mut bytes = vec![];
+    response.take(MAX_SITEMAP_BYTES).read_to_end(&mut bytes).ok()?;
+
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut text = String::new();
+        GzDecoder::new(&bytes[..]).take(MAX_SITEMAP_BYTES).read_to_string(&mut text).ok()?;
+        Some(text)
+    } else {
+        Some(String::from_utf8_lossy(&bytes).to_string())
+    }
+}
+
+// ----- Sitemaps -----
+
+/// `Sitemap:` lines from robots.txt, or `/sitemap.xml` when there are none.
+fn sitemap_locations(client: &reqwest::blocking::Client, origin: &url::Url) -> Vec<String> {
+    let mut locations = vec![];
+    if let Some(robots) = origin.join("/robots.txt").ok().and_then(|u| fetch_text(client, u.as_str())) {
+        for line in robots.lines() {
+            let line = line.trim();
+            if line.get(..8).is_some_and(|prefix| prefix.eq_ignore_ascii_case("sitemap:")) {
+                let location = line[8..].trim();
+                if !location.is_empty() && !locations.iter().any(|l| l == location) {
+                    locations.push(location.to_string());
+                }
+            }
+        }
+    }
+
+    if locations.is_empty() {
+        if let Ok(default) = origin.join("/sitemap.xml") {
+            locations.push(default.to_string());
+        }
+    }
+    locations
+}
+
+/// Splits a sitemap into page URLs (`<url><loc>`) and child sitemaps
+/// (`<sitemap><loc>` in an index). Plain-text sitemaps list one URL per line.
+fn parse_sitemap(body: &str) -> (Vec<String>, Vec<String>) {
+    let mut pages = vec![];
+    let mut sitemaps = vec![];
+
+    if !body.trim_start().starts_with('<') {
+        pages.extend(body.lines()
+            .map(|l| l.trim())
+            .filter(|l| l.starts_with("http://") || l.starts_with("https://"))
+            .map(|l| l.to_string()));
+        return (pages, sitemaps);
+    }
+
+    let mut reader = Reader::from_str(body);
+    let mut path: Vec<String> = vec![];
+    let mut text = XmlText::default();
+
+    // A malformed tail ends the loop but keeps whatever was read before it
+    while let Ok(event) = reader.read_event() {
+        match &event {
+            Event::Start(e) => {
+                path.push(local_name(e));
+                text.take();
+            }
+            Event::End(_) => {
+                let element = path.pop().unwrap_or_default();
+                let location = text.take();
+                if element == "loc" && !location.is_empty() {
+                    match path.last().map(|p| p.as_str()) {
+                        Some("sitemap") => sitemaps.push(location),
+                        Some("url") => pages.push(location),
+                        _ => {}
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => text.push_event(&event),
+        }
+    }
+
+    (pages, sitemaps)
+}
+
+/// Walks sitemaps (following indexes) until `limit` page URLs are collected.
+/// Returns the pages and how many sitemap files were read.
+fn collect_sitemap_pages(
+    client: &reqwest::blocking::Client,
+    locations: Vec<String>,
+    limit: usize,
+) -> (Vec<String>, i32) {
+    let mut pages: Vec<String> = vec![];
+    let mut seen_pages: HashSet<String> = HashSet::new();
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut queue: VecDeque<String> = locations.into();
+    let mut sitemaps_read = 0;
+
+    while let Some(location) = queue.pop_front() {
+        if pages.len() >= limit || visited.len() >= MAX_SITEMAP_FILES {
+            break;
+        }
+        if !visited.insert(location.clone()) {
+            continue;
+        }
+        let Some(body) = fetch_text(client, &location) else { continue };
+        sitemaps_read += 1;
+
+        let (found_pages, child_sitemaps) = parse_sitemap(&body);
+        queue.extend(child_sitemaps);
+        for page in found_pages {
+            if pages.len() >= limit {
+                break;
+            }
+            if seen_pages.insert(page.clone()) {
+                pages.push(page);
+            }
+        }
+    }
+
+    (pages, sitemaps_read)
+}
+
+/// Page URLs from the sitemaps of the site hosting `page_url`.
+pub(crate) fn site_page_urls(page_url: &str, limit: usize) -> Vec<String> {
+    let Ok(origin) = url::Url::parse(page_url) else { return vec![] };
+    let Ok(client) = http_client() else { return vec![] };
+    let locations = sitemap_locations(&client, &origin);
+    collect_sitemap_pages(&client, locations, limit).0
+}
+
+// ----- RSS / Atom -----
+
+/// Feeds advertised by a page through `<link rel="alternate" type="application/rss+xml">`
+/// (or atom+xml), resolved against the page URL.
+pub(crate) fn feed_urls(document: &Html, base: &url::Url) -> Vec<String> {
+    let Ok(selector) = Selector::parse(r#"link[rel~="alternate"][href]"#) else { return vec![] };
+    let mut feeds = vec![];
+    for el in document.select(&selector) {
+        let kind = el.value().attr("type").unwrap_or("").to_lowercase();
+        if !(kind.contains("rss") || kind.contains("atom")) {
+            continue;
+        }
+        if let Some(feed) = el.value().attr("href").and_then(|href| base.join(href).ok()) {
+            let feed = feed.to_string();
+            if !feeds.contains(&feed) {
+                feeds.push(feed);
+            }
+        }
+    }
+    feeds
+}
+
+/// Item links of an RSS (`<item><link>`) or Atom (`<entry><link href>`) feed.
+fn parse_feed(xml: &str) -> Vec<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut links = vec![];
+    let mut in_item = false;
+    let mut in_link = false;
+    let mut text = XmlText::default();
+
+    while let Ok(event) = reader.read_event() {
+        match &event {
+            Event::Start(e) | Event::Empty(e) => match local_name(e).as_str() {
+                "item" | "entry" => in_item = true,
+                "link" if in_item => {
+                    let attrs = xml_attributes(e);
+                    match attrs.get("href") {
+                        // Atom: only the entry's own page, not enclosures or replies
+                        Some(href) => {
+                            if attrs.get("rel").is_none_or(|rel| rel == "alternate") {
+                                links.push(href.clone());
+                            }
+                        }
+                        None => {
+                            in_link = matches!(event, Event::Start(_));
+                            text.take();
+                        }
+                    }
+                }
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"item" | b"entry" => in_item = false,
+                b"link" if in_link => {
+                    in_link = false;
+                    let link = text.take();
+                    if !link.is_empty() {
+                        links.push(link);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ if in_link => text.push_event(&event),
+            _ => {}
+        }
+    }
+
+    links
+}
+
+pub(crate) fn fetch_feed_links(client: &reqwest::blocking::Client, feed_url: &str) -> Vec<String> {
+    fetch_text(client, feed_url).map(|xml| parse_feed(&xml)).unwrap_or_default()
+}
+
+// ----- Seeding -----
+
+/// Bulk-adds the pages listed in a site's sitemaps as nodes linked from the
+/// site's root node, which is created if the void doesn't have it yet.
+/// `url` may be any page on the site; at most `max_pages` (default 200) new
+/// nodes are added, while pages already in the void are just linked. New
+/// pages must pass the session's discovery rules.
+#[tauri::command]
+pub async fn seed_from_sitemap(
+    app: tauri::AppHandle,
+    url: String,
+    max_pages: Option<i32>,
+) -> Result<SitemapSeedResult, String> {
+    let parsed = url::Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let host = parsed.host_str().ok_or("URL has no host")?.to_string();
+    let root_url = crate::clean_link(&parsed.origin().ascii_serialization()).ok_or("Only http(s) sites have sitemaps")?;
+    let max_pages = max_pages.unwrap_or(200).max(0);
+
+    let (pages, sitemaps_read) = std::thread::spawn(move || {
+        let client = http_client()?;
+        let locations = sitemap_locations(&client, &parsed);
+        Ok::<_, String>(collect_sitemap_pages(&client, locations, SEED_SITEMAP_LIMIT))
+    }).join().map_err(|_| "Thread panic")??;
+
+    if pages.is_empty() {
+        return Err(format!("No sitemap pages found for {}", host));
+    }
+
+    let db_path = crate::get_active_db_path(&app)?;
+    let mut conn = crate::open_void_db(&db_path)?;
+    let rules = crate::discovery_rules::load_rule_set(&conn)?;
+
+    let mut existing_urls: HashMap<String, i64> = HashMap::new();
+    {
+        let mut stmt = conn.prepare("SELECT id, url FROM nodes").map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(1)?, row.get::<_, i64>(0)?)))
+            .map_err(|e| e.to_string())?;
+        for (url, id) in rows.flatten() {
+            existing_urls.insert(url, id);
+        }
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    // Nodes added by hand usually keep the trailing slash, discovered ones don't
+    let root_id = match existing_urls.get(&root_url).or_else(|| existing_urls.get(&format!("{}/", root_url))) {
+        Some(&id) => id,
+        None => {
+            let mut rng = rand::thread_rng();
+            tx.execute(
+                "INSERT INTO nodes (url, title, position_x, position_y, position_z, is_alive, created_at)
+                 VALUES (?, ?, ?, ?, ?, 1, datetime('now'))",
+                params![root_url, host, rng.gen_range(-20.0..20.0), rng.gen_range(-15.0..15.0), rng.gen_range(-20.0..20.0)],
+            ).map_err(|e| e.to_string())?;
+            let id = tx.last_insert_rowid();
+            existing_urls.insert(root_url.clone(), id);
+            id
+        }
+    };
+    let (root_x, root_y, root_z): (f64, f64, f64) = tx.query_row(
+        "SELECT position_x, position_y, position_z FROM nodes WHERE id = ?",
+        params![root_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    ).map_err(|e| e.to_string())?;
+    let root_depth = crate::discovery_rules::site_depth(&tx, root_id)?;
+
+    let mut nodes_added = 0;
+    let mut edges_added = 0;
+    let mut urls_filtered = 0;
+    let mut new_node_ids: Vec<i64> = vec![];
+
+    for page in pages.iter().filter_map(|p| crate::clean_link(p)) {
+        if page == root_url {
+            continue;
+        }
+        let target_id = match existing_urls.get(&page) {
+            Some(&id) => id,
+            None => {
+                if nodes_added >= max_pages {
+                    continue;
+                }
+                let depth = match rules.link_depth(&root_url, root_depth, &page) {
+                    Some(depth) if rules.allows(&page) => depth,
+                    _ => {
+                        urls_filtered += 1;
+                        continue;
+                    }
+                };
+                let (x, y, z) = crate::generate_nearby_position(root_x, root_y, root_z);
+                let title = url::Url::parse(&page)
+                    .ok()
+                    .and_then(|u| u.host_str().map(|h| h.to_string()))
+                    .unwrap_or_else(|| host.clone());
+                tx.execute(
+                    "INSERT INTO nodes (url, title, position_x, position_y, position_z, is_alive, created_at, site_depth)
+                     VALUES (?, ?, ?, ?, ?, 1, datetime('now'), ?)",
+                    params![page, title, x, y, z, depth],
+                ).map_err(|e| e.to_string())?;
+                let id = tx.last_insert_rowid();
+                existing_urls.insert(page, id);
+                new_node_ids.push(id);
+                nodes_added += 1;
+                id
+            }
+        };
+
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO edges (source_id, target_id) VALUES (?, ?)",
+            params![root_id, target_id],
+        ).map_err(|e| e.to_string())?;
+        edges_added += inserted as i32;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+
+    if !new_node_ids.is_empty() {
+        crate::layout::relax_nodes_in(
+            &mut conn,
+            &new_node_ids,
+            false,
+            crate::layout::INCREMENTAL_ITERATIONS,
+            crate::layout::DEFAULT_MIN_SEPARATION,
+        ).ok();
+    }
+
+    Ok(SitemapSeedResult {
+        root_node_id: root_id,
+        sitemaps_read,
+        urls_found: pages.len() as i32,
+        nodes_added,
+        edges_added,
+        new_node_ids,
+        urls_filtered,
+    })
+}
@@ -0,0 +1,242 @@
+// ============== VOID STATS ==============
+//
+// Aggregates for the stats dashboard. Everything except the connected
+// component sizes is computed by SQLite; components need a union-find over
+// the edge list, which only touches integer ids.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use rusqlite::{Connection, params};
+
+const DEFAULT_STALE_DAYS: i64 = 30;
+const DEFAULT_GROWTH_DAYS: i64 = 90;
+const DEFAULT_TOP_DOMAINS: i64 = 50;
+
+/// `host` (lowercase, without `www.`) and `tld` for every node, cut out of
+/// the URL with string functions: the host runs from after `://` to the first
+/// `/`, `?` or `:`, and the TLD is whatever follows its last dot.
+const NODE_HOSTS_CTE: &str = "
+    WITH after_scheme AS (
+        SELECT id, lower(substr(url, instr(url, '://') + 3)) AS h FROM nodes
+    ),
+    no_path AS (SELECT id, substr(h, 1, instr(h || '/', '/') - 1) AS h FROM after_scheme),
+    no_query AS (SELECT id, substr(h, 1, instr(h || '?', '?') - 1) AS h FROM no_path),
+    no_port AS (SELECT id, substr(h, 1, instr(h || ':', ':') - 1) AS h FROM no_query),
+    hosts AS (
+        SELECT id, CASE WHEN h LIKE 'www.%' THEN substr(h, 5) ELSE h END AS host FROM no_port
+    ),
+    node_hosts AS (
+        SELECT id, host, substr(host, length(rtrim(host, replace(host, '.', ''))) + 1) AS tld FROM hosts
+    )";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountEntry {
+    pub name: String,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyCount {
+    /// `YYYY-MM-DD`
+    pub day: String,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlCoverage {
+    pub never: i32,
+    /// Crawled more than `stale_days` ago
+    pub stale: i32,
+    pub fresh: i32,
+    pub stale_days: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoidStats {
+    pub total_nodes: i32,
+    pub total_edges: i32,
+    pub alive_nodes: i32,
+    pub dead_nodes: i32,
+    pub domain_count: i32,
+    /// Most populated domains first
+    pub top_domains: Vec<CountEntry>,
+    pub tlds: Vec<CountEntry>,
+    pub crawl_coverage: CrawlCoverage,
+    /// Oldest day first; days without new nodes are left out
+    pub nodes_per_day: Vec<DailyCount>,
+    /// Links per node, counting both directions
+    pub average_degree: f64,
+    pub isolated_nodes: i32,
+    pub component_count: i32,
+    pub largest_component: i32,
+}
+
+fn count_entries(conn: &Connection, sql: &str, limit: i64) -> Result<Vec<CountEntry>, String> {
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt.query_map(params![limit], |row| {
+        Ok(CountEntry { name: row.get(0)?, count: row.get(1)? })
+    }).map_err(|e| e.to_string())?;
+    Ok(rows.flatten().collect())
+}
+
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Connected components (ignoring edge direction) as lists of node ids,
+/// largest first. Isolated nodes are components of one.
+pub(crate) fn connected_components_in(conn: &Connection) -> Result<Vec<Vec<i64>>, String> {
+    let ids: Vec<i64> = {
+        let mut stmt = conn.prepare("SELECT id FROM nodes ORDER BY id").map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| row.get(0)).map_err(|e| e.to_string())?;
+        rows.flatten().collect()
+    };
+    let index: HashMap<i64, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let mut parent: Vec<usize> = (0..ids.len()).collect();
+    let mut size: Vec<usize> = vec![1; ids.len()];
+
+    let mut stmt = conn.prepare("SELECT source_id, target_id FROM edges").map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))
+        .map_err(|e| e.to_string())?;
+    for (source, target) in rows.flatten() {
+        let (Some(&a), Some(&b)) = (index.get(&source), index.get(&target)) else {
+            continue;
+        };
+        let (mut a, mut b) = (find_root(&mut parent, a), find_root(&mut parent, b));
+        if a == b {
+            continue;
+        }
+        if size[a] < size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        parent[b] = a;
+        size[a] += size[b];
+    }
+
+    let mut members: HashMap<usize, Vec<i64>> = HashMap::new();
+    for (i, &id) in ids.iter().enumerate() {
+        let root = find_root(&mut parent, i);
+        members.entry(root).or_default().push(id);
+    }
+    let mut components: Vec<Vec<i64>> = members.into_values().collect();
+    components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+    Ok(components)
+}
+
+pub(crate) fn void_stats_in(
+    conn: &Connection,
+    stale_days: i64,
+    growth_days: i64,
+    top_domains: i64,
+) -> Result<VoidStats, String> {
+    let (total_nodes, alive_nodes, never, fresh) = conn.query_row(
+        "SELECT COUNT(*),
+                COALESCE(SUM(is_alive = 1), 0),
+                COALESCE(SUM(last_crawled IS NULL), 0),
+                COALESCE(SUM(last_crawled >= datetime('now', '-' || ?1 || ' days')), 0)
+         FROM nodes",
+        params![stale_days],
+        |row| Ok((row.get::<_, i32>(0)?, row.get::<_, i32>(1)?, row.get::<_, i32>(2)?, row.get::<_, i32>(3)?)),
+    ).map_err(|e| e.to_string())?;
+
+    let total_edges: i32 = conn.query_row("SELECT COUNT(*) FROM edges", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    let isolated_nodes: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM nodes n
+         WHERE NOT EXISTS (SELECT 1 FROM edges WHERE source_id = n.id)
+           AND NOT EXISTS (SELECT 1 FROM edges WHERE target_id = n.id)",
+        [],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+
+    let domain_count: i32 = conn.query_row(
+        &format!("{} SELECT COUNT(DISTINCT host) FROM node_hosts WHERE host != ''", NODE_HOSTS_CTE),
+        [],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+
+    let top_domains = count_entries(
+        conn,
+        &format!(
+            "{} SELECT host, COUNT(*) AS c FROM node_hosts WHERE host != ''
+             GROUP BY host ORDER BY c DESC, host LIMIT ?1",
+            NODE_HOSTS_CTE
+        ),
+        top_domains,
+    )?;
+
+    // IP addresses have no TLD
+    let tlds = count_entries(
+        conn,
+        &format!(
+            "{} SELECT tld, COUNT(*) AS c FROM node_hosts
+             WHERE tld != '' AND tld GLOB '*[a-z]*'
+             GROUP BY tld ORDER BY c DESC, tld LIMIT ?1",
+            NODE_HOSTS_CTE
+        ),
+        -1,
+    )?;
+
+    let nodes_per_day = {
+        let mut stmt = conn.prepare(
+            "SELECT date(created_at) AS day, COUNT(*) FROM nodes
+             WHERE created_at >= date('now', '-' || ?1 || ' days')
+             GROUP BY day ORDER BY day"
+        ).map_err(|e| e.to_string())?;
+        let rows = stmt.query_map(params![growth_days], |row| {
+            Ok(DailyCount { day: row.get(0)?, count: row.get(1)? })
+        }).map_err(|e| e.to_string())?;
+        rows.flatten().collect()
+    };
+
+    let components = connected_components_in(conn)?;
+
+    Ok(VoidStats {
+        total_nodes,
+        total_edges,
+        alive_nodes,
+        dead_nodes: total_nodes - alive_nodes,
+        domain_count,
+        top_domains,
+        tlds,
+        crawl_coverage: CrawlCoverage {
+            never,
+            stale: total_nodes - never - fresh,
+            fresh,
+            stale_days,
+        },
+        nodes_per_day,
+        average_degree: if total_nodes > 0 { 2.0 * total_edges as f64 / total_nodes as f64 } else { 0.0 },
+        isolated_nodes,
+        component_count: components.len() as i32,
+        largest_component: components.first().map_or(0, |c| c.len() as i32),
+    })
+}
+
+/// Dashboard numbers for the active void. Nodes crawled within `stale_days`
+/// (default 30) count as fresh; `growth_days` (default 90) bounds the
+/// nodes-per-day series and `top_domains` (default 50) the domain list.
+#[tauri::command]
+pub async fn get_void_stats(
+    app: tauri::AppHandle,
+    stale_days: Option<i64>,
+    growth_days: Option<i64>,
+    top_domains: Option<i64>,
+) -> Result<VoidStats, String> {
+    let db_path = crate::get_active_db_path(&app)?;
+
+    std::thread::spawn(move || {
+        let conn = crate::open_void_db(&db_path)?;
+        void_stats_in(
+            &conn,
+            stale_days.filter(|d| *d > 0).unwrap_or(DEFAULT_STALE_DAYS),
+            growth_days.filter(|d| *d > 0).unwrap_or(DEFAULT_GROWTH_DAYS),
+            top_domains.filter(|n| *n > 0).unwrap_or(DEFAULT_TOP_DOMAINS),
+        )
+    }).join().map_err(|_| "Thread panic")?
+}